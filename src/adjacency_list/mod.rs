@@ -1,4 +1,5 @@
-use crate::{board::GridNode, NodeStatus};
+use crate::board::{Direction, GridDimensions, GridNode, DIRECTIONS};
+use crate::NodeStatus;
 
 #[derive(Debug, Hash)]
 pub struct Edge {
@@ -10,7 +11,7 @@ pub struct AdjacencyEntry {
     pub edges: Vec<Edge>,
 }
 
-fn is_node_on(node_id: usize, nodes: &Vec<GridNode>) -> bool {
+fn is_node_on(node_id: usize, nodes: &[GridNode]) -> bool {
     if let NodeStatus::Off = nodes[node_id].node_status {
         return false;
     }
@@ -22,38 +23,44 @@ fn add_edge(edges: &mut Vec<Edge>, to: usize, weight: usize) {
     edges.push(Edge { to, weight });
 }
 
-fn get_edges_to_neighbors(node_id: usize, nodes: &Vec<GridNode>) -> Vec<Edge> {
-    let mut edges_to_neighbors = Vec::new();
-    let side_length = (nodes.len() as f64).sqrt() as usize;
-    let row = node_id / side_length;
-    let col = node_id % side_length;
-
-    //the value 1 is the edge weight. here all edges have the same weight for demo purposes
-    if col > 0 && is_node_on(node_id - 1, nodes) {
-        add_edge(&mut edges_to_neighbors, node_id - 1, 1);
-    }
+/// One-way cells can only be entered and left moving the way they point, so an edge between
+/// neighbors may exist in one direction only.
+pub fn is_move_allowed(from_direction: Option<Direction>, to_direction: Option<Direction>, direction: Direction) -> bool {
+    [from_direction, to_direction].into_iter().flatten().all(|one_way| one_way == direction)
+}
 
-    if col < side_length - 1 && is_node_on(node_id + 1, nodes) {
-        add_edge(&mut edges_to_neighbors, node_id + 1, 1);
-    }
+fn get_edges_to_neighbors(node_id: usize, nodes: &[GridNode], dimensions: GridDimensions) -> Vec<Edge> {
+    let mut edges_to_neighbors = Vec::new();
 
-    if row > 0 && is_node_on(node_id - side_length, nodes) {
-        add_edge(&mut edges_to_neighbors, node_id - side_length, 1);
-    }
+    //the edge weight is the cost of entering the neighboring node
+    for direction in DIRECTIONS {
+        let Some(neighbor) = direction.get_neighbor(node_id, dimensions) else {
+            continue;
+        };
 
-    if row < side_length - 1 && is_node_on(node_id + side_length, nodes) {
-        add_edge(&mut edges_to_neighbors, node_id + side_length, 1);
+        if is_node_on(neighbor, nodes) && is_move_allowed(nodes[node_id].direction, nodes[neighbor].direction, direction) {
+            add_edge(&mut edges_to_neighbors, neighbor, nodes[neighbor].weight);
+        }
     }
 
     edges_to_neighbors
 }
 
-pub fn create_adjacency_list(nodes: &Vec<GridNode>) -> Vec<AdjacencyEntry> {
+/// The jump from a portal to its partner, wherever that lies on the board.
+fn get_edge_through_portal(node_id: usize, nodes: &[GridNode]) -> Option<Edge> {
+    let portal = nodes[node_id].portal?;
+    is_node_on(portal.partner, nodes).then_some(Edge { to: portal.partner, weight: portal.cost })
+}
+
+pub fn create_adjacency_list(nodes: &[GridNode], dimensions: GridDimensions) -> Vec<AdjacencyEntry> {
     nodes
         .iter()
         .enumerate()
         .map(|(i, _node)| AdjacencyEntry {
-            edges: get_edges_to_neighbors(i, nodes),
+            edges: get_edges_to_neighbors(i, nodes, dimensions)
+                .into_iter()
+                .chain(get_edge_through_portal(i, nodes))
+                .collect(),
         })
         .collect()
 }
@@ -61,6 +68,8 @@ pub fn create_adjacency_list(nodes: &Vec<GridNode>) -> Vec<AdjacencyEntry> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::board::Portal;
+    use crate::dijkstras::DijkstrasTraversal;
     use crate::tests_common;
 
     #[test]
@@ -68,7 +77,7 @@ mod tests {
         let mock_grid = tests_common::get_mock_grid();
         let mock_node_statuses = tests_common::get_mock_nodes(mock_grid);
 
-        let adjacency_list = create_adjacency_list(&mock_node_statuses);
+        let adjacency_list = create_adjacency_list(&mock_node_statuses, tests_common::MOCK_DIMENSIONS);
 
         let number_of_expected_edges_list = vec![
             1, 3, 2, 2, 1, 1, 2, 3, 3, 2, 3, 2, 4, 3, 1, 1, 2, 3, 4, 3, 2, 3, 3, 3, 1, 1, 2, 3, 2,
@@ -91,6 +100,101 @@ mod tests {
     }
 
     #[test]
+    fn rectangular_boards_use_their_width_as_row_stride() {
+        let nodes = tests_common::get_mock_nodes(vec![0; 6]);
+        let dimensions = GridDimensions { columns: 3, rows: 2 };
+
+        let adjacency_list = create_adjacency_list(&nodes, dimensions);
+        let neighbors_of = |node_id: usize| {
+            let mut neighbors: Vec<usize> = adjacency_list[node_id].edges.iter().map(|edge| edge.to).collect();
+            neighbors.sort();
+            neighbors
+        };
+
+        assert_eq!(neighbors_of(0), vec![1, 3]);
+        assert_eq!(neighbors_of(2), vec![1, 5]);
+        assert_eq!(neighbors_of(4), vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn one_way_cells_only_have_edges_along_their_arrow() {
+        let mut nodes = tests_common::get_mock_nodes(vec![0; 9]);
+        let dimensions = GridDimensions::square(3);
+        nodes[4].direction = Some(Direction::Right);
+
+        let adjacency_list = create_adjacency_list(&nodes, dimensions);
+        let has_edge = |from: usize, to: usize| adjacency_list[from].edges.iter().any(|edge| edge.to == to);
+
+        assert!(has_edge(3, 4) && has_edge(4, 5));
+        assert!(!has_edge(4, 3) && !has_edge(5, 4));
+        // side entries and exits would cross the arrow
+        assert!(!has_edge(1, 4) && !has_edge(4, 1) && !has_edge(7, 4) && !has_edge(4, 7));
+        assert_eq!(adjacency_list[4].edges.len(), 1);
+    }
+
+    #[test]
+    fn one_way_corridors_make_the_way_back_longer() {
+        // a wall splits the board but for a gap in the middle, which is made one-way downwards
+        let mut nodes = tests_common::get_mock_nodes(vec![
+            0, 0, 0, 0, 0,
+            1, 1, 0, 1, 1,
+            0, 0, 0, 0, 0,
+        ]);
+        let dimensions = GridDimensions { columns: 5, rows: 3 };
+        nodes[7].direction = Some(Direction::Down);
+
+        let there = DijkstrasTraversal::run(&nodes, dimensions, 2, 12);
+        let back = DijkstrasTraversal::run(&nodes, dimensions, 12, 2);
+
+        assert_eq!(there.get_cost(), Some(2));
+        assert_eq!(there.get_path(), vec![7]);
+        assert_eq!(back.get_cost(), None);
+
+        // with a second gap at the side, the way back takes the detour
+        nodes[5].node_status = NodeStatus::On;
+        let back = DijkstrasTraversal::run(&nodes, dimensions, 12, 2);
+        assert_eq!(back.get_cost(), Some(6));
+        assert!(!back.get_path().contains(&7));
+    }
+
+    fn link_portals(nodes: &mut [GridNode], first: usize, second: usize, cost: usize) {
+        nodes[first].portal = Some(Portal { partner: second, cost });
+        nodes[second].portal = Some(Portal { partner: first, cost });
+    }
+
+    #[test]
+    fn portals_add_an_edge_to_their_partner() {
+        let mut nodes = tests_common::get_mock_nodes(vec![0; 9]);
+        let dimensions = GridDimensions::square(3);
+        link_portals(&mut nodes, 0, 8, 4);
+
+        let adjacency_list = create_adjacency_list(&nodes, dimensions);
+        let jump = |from: usize| adjacency_list[from].edges.iter().find(|edge| edge.to == 8 - from).map(|edge| edge.weight);
+
+        assert_eq!(jump(0), Some(4));
+        assert_eq!(jump(8), Some(4));
+        assert_eq!(adjacency_list[0].edges.len(), 3);
+
+        // a walled-in partner cannot be jumped to
+        nodes[8].node_status = NodeStatus::Off;
+        assert!(get_edge_through_portal(0, &nodes).is_none());
+    }
+
+    #[test]
+    fn paths_take_a_portal_only_while_it_is_cheaper() {
+        let mut nodes = tests_common::get_mock_nodes(vec![0; 100]);
+        link_portals(&mut nodes, 1, 98, 3);
+
+        let through_portal = DijkstrasTraversal::run(&nodes, tests_common::MOCK_DIMENSIONS, 0, 99);
+        assert_eq!(through_portal.get_cost(), Some(5));
+        assert_eq!(through_portal.get_path(), vec![1, 98]);
+
+        link_portals(&mut nodes, 1, 98, 30);
+        assert_eq!(DijkstrasTraversal::run(&nodes, tests_common::MOCK_DIMENSIONS, 0, 99).get_cost(), Some(18));
+    }
+
+    #[test]
+    #[allow(clippy::needless_range_loop)]
     fn correctly_identifies_off_nodes() {
         let mock_grid = tests_common::get_mock_grid();
         let mock_node_statuses = tests_common::get_mock_nodes(mock_grid);
@@ -0,0 +1,264 @@
+use crate::board::{GridDimensions, GridNode};
+use crate::dijkstras::{Algorithm, DijkstrasTraversal, ALGORITHMS};
+use crate::obstacles;
+use crate::utils;
+use crate::DEFAULT_WEIGHT;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchmarkConfig {
+    pub board_count: usize,
+    pub columns: usize,
+    pub rows: usize,
+    pub wall_density: f64,
+    pub seed: u64,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        BenchmarkConfig {
+            board_count: 20,
+            columns: 50,
+            rows: 50,
+            wall_density: 0.3,
+            seed: 1,
+        }
+    }
+}
+
+impl BenchmarkConfig {
+    fn get_dimensions(&self) -> GridDimensions {
+        GridDimensions {
+            columns: self.columns,
+            rows: self.rows,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchmarkRow {
+    pub algorithm: &'static str,
+    pub solved: usize,
+    pub mean_visited: f64,
+    pub median_visited: f64,
+    pub mean_runtime_ms: f64,
+    pub median_runtime_ms: f64,
+    /// Average extra cost over the optimal path, as a percentage, on boards where a path exists.
+    pub mean_optimality_gap: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchmarkReport {
+    pub config: BenchmarkConfig,
+    pub solvable_boards: usize,
+    pub rows: Vec<BenchmarkRow>,
+}
+
+struct Sample {
+    visited: usize,
+    runtime_ms: f64,
+    cost: Option<usize>,
+}
+
+/// Board `index` of a run uses `seed + index`, wrapping past `u64::MAX`, so a single board can be reproduced in the app.
+pub(crate) fn create_random_board(config: &BenchmarkConfig, index: usize) -> Vec<GridNode> {
+    let num_nodes = config.get_dimensions().get_num_nodes();
+
+    obstacles::scatter_walls(num_nodes, config.wall_density, config.seed.wrapping_add(index as u64), 0, num_nodes - 1)
+        .into_iter()
+        .map(|node_status| GridNode::build_preview(node_status, DEFAULT_WEIGHT))
+        .collect()
+}
+
+pub fn run_benchmark(config: &BenchmarkConfig) -> BenchmarkReport {
+    let mut samples: Vec<Vec<Sample>> = ALGORITHMS.iter().map(|_| Vec::new()).collect();
+
+    (0..config.board_count).for_each(|index| {
+        let nodes = create_random_board(config, index);
+        let end_node_id = nodes.len() - 1;
+
+        ALGORITHMS.iter().enumerate().for_each(|(algorithm_index, &algorithm)| {
+            let started_at = utils::now_ms();
+            let traversal = DijkstrasTraversal::run_with_algorithm(&nodes, config.get_dimensions(), 0, end_node_id, algorithm);
+            let runtime_ms = utils::now_ms() - started_at;
+
+            samples[algorithm_index].push(Sample {
+                visited: traversal.get_visited_count(),
+                runtime_ms,
+                cost: traversal.get_cost(),
+            });
+        });
+    });
+
+    let dijkstra_index = ALGORITHMS.iter().position(|&algorithm| algorithm == Algorithm::Dijkstra).unwrap();
+    let optimal_costs: Vec<Option<usize>> = samples[dijkstra_index].iter().map(|sample| sample.cost).collect();
+
+    let rows = ALGORITHMS
+        .iter()
+        .zip(samples.iter())
+        .map(|(algorithm, algorithm_samples)| {
+            let visited: Vec<f64> = algorithm_samples.iter().map(|sample| sample.visited as f64).collect();
+            let runtimes: Vec<f64> = algorithm_samples.iter().map(|sample| sample.runtime_ms).collect();
+            let gaps: Vec<f64> = algorithm_samples
+                .iter()
+                .zip(optimal_costs.iter())
+                .filter_map(|(sample, optimal_cost)| match (sample.cost, optimal_cost) {
+                    (Some(cost), Some(optimal_cost)) if *optimal_cost > 0 => {
+                        Some((cost as f64 - *optimal_cost as f64) / *optimal_cost as f64 * 100.0)
+                    }
+                    _ => None,
+                })
+                .collect();
+
+            BenchmarkRow {
+                algorithm: algorithm.get_name(),
+                solved: algorithm_samples.iter().filter(|sample| sample.cost.is_some()).count(),
+                mean_visited: get_mean(&visited),
+                median_visited: get_median(&visited),
+                mean_runtime_ms: get_mean(&runtimes),
+                median_runtime_ms: get_median(&runtimes),
+                mean_optimality_gap: get_mean(&gaps),
+            }
+        })
+        .collect();
+
+    BenchmarkReport {
+        config: config.clone(),
+        solvable_boards: optimal_costs.iter().filter(|cost| cost.is_some()).count(),
+        rows,
+    }
+}
+
+fn get_mean(values: &[f64]) -> f64 {
+    match values.len() {
+        0 => 0.0,
+        len => values.iter().sum::<f64>() / len as f64,
+    }
+}
+
+fn get_median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    match sorted.len() {
+        0 => 0.0,
+        len if len % 2 == 0 => (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0,
+        len => sorted[len / 2],
+    }
+}
+
+impl BenchmarkReport {
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "algorithm,boards,solved,mean_visited,median_visited,mean_runtime_ms,median_runtime_ms,mean_optimality_gap_percent\n",
+        );
+
+        self.rows.iter().for_each(|row| {
+            csv.push_str(&format!(
+                "{},{},{},{:.2},{:.2},{:.4},{:.4},{:.2}\n",
+                row.algorithm,
+                self.config.board_count,
+                row.solved,
+                row.mean_visited,
+                row.median_visited,
+                row.mean_runtime_ms,
+                row.median_runtime_ms,
+                row.mean_optimality_gap,
+            ));
+        });
+
+        csv
+    }
+
+    pub fn to_table(&self) -> String {
+        let mut table = format!(
+            "{} boards of {}x{}, wall density {:.2}, seed {} ({} solvable)\n\n",
+            self.config.board_count,
+            self.config.columns,
+            self.config.rows,
+            self.config.wall_density,
+            self.config.seed,
+            self.solvable_boards,
+        );
+        table.push_str(&format!(
+            "{:<18} {:>7} {:>12} {:>14} {:>13} {:>15} {:>8}\n",
+            "algorithm", "solved", "mean visited", "median visited", "mean time ms", "median time ms", "gap %",
+        ));
+
+        self.rows.iter().for_each(|row| {
+            table.push_str(&format!(
+                "{:<18} {:>7} {:>12.1} {:>14.1} {:>13.4} {:>15.4} {:>8.2}\n",
+                row.algorithm,
+                row.solved,
+                row.mean_visited,
+                row.median_visited,
+                row.mean_runtime_ms,
+                row.median_runtime_ms,
+                row.mean_optimality_gap,
+            ));
+        });
+
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn benchmark_reports_every_algorithm_with_optimal_dijkstra() {
+        let config = BenchmarkConfig {
+            board_count: 6,
+            columns: 12,
+            rows: 10,
+            wall_density: 0.25,
+            seed: 5,
+        };
+
+        let report = run_benchmark(&config);
+        let dijkstra = &report.rows[0];
+        let a_star = &report.rows[1];
+
+        assert_eq!(report.rows.len(), ALGORITHMS.len());
+        assert_eq!(dijkstra.solved, report.solvable_boards);
+        assert_eq!(dijkstra.mean_optimality_gap, 0.0);
+        assert_eq!(a_star.mean_optimality_gap, 0.0);
+        assert!(a_star.mean_visited <= dijkstra.mean_visited);
+        assert!(report.rows.iter().all(|row| row.mean_optimality_gap >= 0.0));
+
+        let rerun = run_benchmark(&config);
+        assert_eq!(rerun.solvable_boards, report.solvable_boards);
+        assert!(rerun.rows.iter().zip(report.rows.iter()).all(|(first, second)| first.mean_visited == second.mean_visited));
+    }
+
+    #[test]
+    fn csv_has_header_and_one_line_per_algorithm() {
+        let report = run_benchmark(&BenchmarkConfig { board_count: 2, columns: 8, rows: 8, ..BenchmarkConfig::default() });
+        let csv = report.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), ALGORITHMS.len() + 1);
+        assert!(lines[0].starts_with("algorithm,boards,solved"));
+        assert!(lines[1].starts_with("Dijkstra,2,"));
+    }
+
+    #[test]
+    fn seeds_near_the_top_wrap_around() {
+        let config = BenchmarkConfig { board_count: 3, columns: 8, rows: 8, seed: u64::MAX - 1, ..BenchmarkConfig::default() };
+
+        let report = run_benchmark(&config);
+
+        assert!(report.solvable_boards <= 3);
+        assert_eq!(
+            create_random_board(&config, 2).iter().map(|grid_node| grid_node.node_status.clone()).collect::<Vec<_>>(),
+            create_random_board(&BenchmarkConfig { seed: 0, ..config }, 0).iter().map(|grid_node| grid_node.node_status.clone()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn median_handles_even_and_odd_lengths() {
+        assert_eq!(get_median(&[3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(get_median(&[4.0, 1.0, 2.0, 3.0]), 2.5);
+        assert_eq!(get_median(&[]), 0.0);
+    }
+}
@@ -0,0 +1,57 @@
+use std::process;
+
+use dijkstras_rust::benchmark::{self, BenchmarkConfig};
+
+const USAGE: &str = "usage: benchmark [--boards N] [--size SIDE | --columns N --rows N] [--density 0..1] [--seed N] [--csv PATH]";
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> T {
+    value.and_then(|value| value.parse().ok()).unwrap_or_else(|| {
+        eprintln!("invalid or missing value for {}\n{}", flag, USAGE);
+        process::exit(2);
+    })
+}
+
+fn main() {
+    let mut config = BenchmarkConfig::default();
+    let mut csv_path = None;
+    let mut args = std::env::args().skip(1);
+
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--boards" => config.board_count = parse_value(&flag, args.next()),
+            "--size" => {
+                config.columns = parse_value(&flag, args.next());
+                config.rows = config.columns;
+            }
+            "--columns" => config.columns = parse_value(&flag, args.next()),
+            "--rows" => config.rows = parse_value(&flag, args.next()),
+            "--density" => config.wall_density = parse_value(&flag, args.next()),
+            "--seed" => config.seed = parse_value(&flag, args.next()),
+            "--csv" => csv_path = Some(parse_value::<String>(&flag, args.next())),
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => {
+                eprintln!("unknown argument {}\n{}", flag, USAGE);
+                process::exit(2);
+            }
+        }
+    }
+
+    if config.columns < 2 || config.rows < 2 || !(0.0..=1.0).contains(&config.wall_density) {
+        eprintln!("size must be at least 2 and density between 0 and 1\n{}", USAGE);
+        process::exit(2);
+    }
+
+    let report = benchmark::run_benchmark(&config);
+    print!("{}", report.to_table());
+
+    if let Some(csv_path) = csv_path {
+        if let Err(error) = std::fs::write(&csv_path, report.to_csv()) {
+            eprintln!("could not write {}: {}", csv_path, error);
+            process::exit(1);
+        }
+        println!("\nwrote {}", csv_path);
+    }
+}
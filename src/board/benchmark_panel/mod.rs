@@ -0,0 +1,126 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::benchmark::{self, BenchmarkConfig, BenchmarkReport};
+use crate::utils;
+
+use super::GridDimensions;
+
+#[derive(Properties, PartialEq)]
+pub struct BenchmarkPanelProps {
+    pub dimensions: GridDimensions,
+    pub on_close: Callback<MouseEvent>,
+}
+
+#[function_component]
+pub fn BenchmarkPanel(props: &BenchmarkPanelProps) -> Html {
+    let GridDimensions { columns, rows } = props.dimensions;
+    let config = use_mut_ref(|| BenchmarkConfig { columns, rows, ..BenchmarkConfig::default() });
+    let report = use_state(|| Option::<BenchmarkReport>::None);
+
+    let handle_change_board_count = {
+        let config = config.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(board_count) = input.value().parse::<usize>() {
+                config.borrow_mut().board_count = board_count.max(1);
+            }
+        })
+    };
+
+    let handle_change_density = {
+        let config = config.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(density_percent) = input.value().parse::<f64>() {
+                config.borrow_mut().wall_density = (density_percent / 100.0).clamp(0.0, 1.0);
+            }
+        })
+    };
+
+    let handle_change_seed = {
+        let config = config.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(seed) = input.value().parse::<u64>() {
+                config.borrow_mut().seed = seed;
+            }
+        })
+    };
+
+    let handle_run = {
+        let config = config.clone();
+        let report = report.clone();
+
+        Callback::from(move |_| {
+            report.set(Some(benchmark::run_benchmark(&config.borrow())));
+        })
+    };
+
+    let handle_download_csv = {
+        let report = report.clone();
+
+        Callback::from(move |_| {
+            if let Some(report) = report.as_ref() {
+                utils::download_file("benchmark.csv", "text/csv", report.to_csv().as_bytes());
+            }
+        })
+    };
+
+    let config_borrow = config.borrow();
+
+    html! {
+        <div class="flex flex-col gap-8 items-center text-zinc-400">
+            <div class="flex gap-6 items-center">
+                <label class="flex items-center gap-2">{"Boards"}
+                    <input class="w-20 bg-gray-800 text-white p-1 rounded-md" type="number" min="1" value={config_borrow.board_count.to_string()} onchange={handle_change_board_count} />
+                </label>
+                <label class="flex items-center gap-2">{"Wall density %"}
+                    <input class="w-20 bg-gray-800 text-white p-1 rounded-md" type="number" min="0" max="100" value={(config_borrow.wall_density * 100.0).to_string()} onchange={handle_change_density} />
+                </label>
+                <label class="flex items-center gap-2">{"Seed"}
+                    <input class="w-24 bg-gray-800 text-white p-1 rounded-md" type="number" min="0" value={config_borrow.seed.to_string()} onchange={handle_change_seed} />
+                </label>
+                <button class="text-white border-2 border-green-600 p-2 rounded-md hover:bg-green-600 hover:text-black" onclick={handle_run}>{"Run benchmark"}</button>
+                <button class="text-white border-2 border-zinc-600 p-2 rounded-md hover:bg-zinc-600 hover:text-black" onclick={&props.on_close}>{"Back to editor"}</button>
+            </div>
+
+            if let Some(report) = report.as_ref() {
+                <p>{format!(
+                    "{} boards of {}x{}, {} solvable",
+                    report.config.board_count, report.config.columns, report.config.rows, report.solvable_boards,
+                )}</p>
+                <table class="text-zinc-300 border-2 border-zinc-700">
+                    <thead>
+                        <tr class="text-zinc-500">
+                            <th class="px-4 py-1 text-left">{"algorithm"}</th>
+                            <th class="px-4 py-1 text-right">{"solved"}</th>
+                            <th class="px-4 py-1 text-right">{"mean visited"}</th>
+                            <th class="px-4 py-1 text-right">{"median visited"}</th>
+                            <th class="px-4 py-1 text-right">{"mean time (ms)"}</th>
+                            <th class="px-4 py-1 text-right">{"median time (ms)"}</th>
+                            <th class="px-4 py-1 text-right">{"optimality gap"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {report.rows.iter().map(|row| html! {
+                            <tr>
+                                <td class="px-4 py-1">{row.algorithm}</td>
+                                <td class="px-4 py-1 text-right">{row.solved}</td>
+                                <td class="px-4 py-1 text-right">{format!("{:.1}", row.mean_visited)}</td>
+                                <td class="px-4 py-1 text-right">{format!("{:.1}", row.median_visited)}</td>
+                                <td class="px-4 py-1 text-right">{format!("{:.3}", row.mean_runtime_ms)}</td>
+                                <td class="px-4 py-1 text-right">{format!("{:.3}", row.median_runtime_ms)}</td>
+                                <td class="px-4 py-1 text-right">{format!("{:.2}%", row.mean_optimality_gap)}</td>
+                            </tr>
+                        }).collect::<Html>()}
+                    </tbody>
+                </table>
+                <button class="text-white border-2 border-sky-700 p-2 rounded-md hover:bg-sky-700 hover:text-black" onclick={handle_download_csv}>{"Download CSV"}</button>
+            }
+        </div>
+    }
+}
@@ -9,11 +9,126 @@ use crate::NodeStatus;
 pub type Nodes = Rc<RefCell<Vec<GridNode>>>;
 pub type CurrentStartNode = Rc<RefCell<Option<usize>>>;
 pub type CurrentEndNode = Rc<RefCell<Option<usize>>>;
+/// The cell a drag stroke started from for the shape tools, or last reached for freehand.
+pub type CurrentStroke = Rc<RefCell<Option<usize>>>;
 
 pub struct GridNode {
     pub node: VNode,
     pub node_ref: NodeRef,
     pub node_status: NodeStatus,
+    pub weight: usize,
+    /// Set on one-way cells, which can only be entered and left moving this way.
+    pub direction: Option<Direction>,
+    pub portal: Option<Portal>,
+}
+
+/// One end of a portal pair: from this cell a move can jump straight to `partner` for `cost`.
+/// Both ends carry the same cost, so the jump costs the same either way.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Portal {
+    pub partner: usize,
+    pub cost: usize,
+}
+
+pub const DEFAULT_PORTAL_COST: usize = 1;
+pub const MAX_PORTAL_COST: usize = 99;
+
+/// Width and height of a board in cells; node ids run row by row.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridDimensions {
+    pub columns: usize,
+    pub rows: usize,
+}
+
+impl GridDimensions {
+    pub const fn square(side_length: usize) -> Self {
+        GridDimensions {
+            columns: side_length,
+            rows: side_length,
+        }
+    }
+
+    pub fn get_num_nodes(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    pub fn get_row(&self, node_id: usize) -> usize {
+        node_id / self.columns
+    }
+
+    pub fn get_column(&self, node_id: usize) -> usize {
+        node_id % self.columns
+    }
+
+    /// The cells above, below, left and right of a cell that lie on the board.
+    pub fn get_neighbors(&self, node_id: usize) -> impl Iterator<Item = usize> {
+        let dimensions = *self;
+        DIRECTIONS.into_iter().filter_map(move |direction| direction.get_neighbor(node_id, dimensions))
+    }
+
+    /// Inline CSS that lays the cells out and keeps them square whatever the board's shape.
+    pub fn get_grid_style(&self) -> String {
+        format!(
+            "grid-template-columns: repeat({}, minmax(0, 1fr)); grid-template-rows: repeat({}, minmax(0, 1fr)); aspect-ratio: {} / {};",
+            self.columns, self.rows, self.columns, self.rows,
+        )
+    }
+}
+
+/// A move to one of a cell's four neighbors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    /// The cell one move this way, if it is still on the board.
+    pub fn get_neighbor(&self, node_id: usize, dimensions: GridDimensions) -> Option<usize> {
+        let (row, column) = (dimensions.get_row(node_id), dimensions.get_column(node_id));
+
+        match self {
+            Direction::Left => (column > 0).then(|| node_id - 1),
+            Direction::Right => (column + 1 < dimensions.columns).then(|| node_id + 1),
+            Direction::Up => (row > 0).then(|| node_id - dimensions.columns),
+            Direction::Down => (row + 1 < dimensions.rows).then(|| node_id + dimensions.columns),
+        }
+    }
+
+    /// The way to move from one cell to a neighboring one.
+    pub fn between(from: usize, to: usize, dimensions: GridDimensions) -> Option<Self> {
+        DIRECTIONS.into_iter().find(|direction| direction.get_neighbor(from, dimensions) == Some(to))
+    }
+
+    pub fn get_arrow(&self) -> &'static str {
+        match self {
+            Direction::Left => "←",
+            Direction::Right => "→",
+            Direction::Up => "↑",
+            Direction::Down => "↓",
+        }
+    }
+}
+
+/// In the order the adjacency list has always listed neighbors, which keeps tie-breaks stable.
+pub const DIRECTIONS: [Direction; 4] = [Direction::Left, Direction::Right, Direction::Up, Direction::Down];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Endpoint {
+    Start,
+    End,
+}
+
+impl Endpoint {
+    pub fn from_status(node_status: &NodeStatus) -> Option<Self> {
+        match node_status {
+            NodeStatus::Start => Some(Endpoint::Start),
+            NodeStatus::End => Some(Endpoint::End),
+            _ => None,
+        }
+    }
 }
 
 pub enum BoardStatus {
@@ -23,6 +138,147 @@ pub enum BoardStatus {
     Visualized,
 }
 
+/// How the editable board is drawn; the canvas copes with far larger boards than one element per cell.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Renderer {
+    Dom,
+    Canvas,
+}
+
+impl Renderer {
+    pub fn from_value(value: &str) -> Option<Self> {
+        RENDERERS.into_iter().find(|renderer| renderer.get_value() == value)
+    }
+
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            Renderer::Dom => "dom",
+            Renderer::Canvas => "canvas",
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Renderer::Dom => "cells (DOM)",
+            Renderer::Canvas => "canvas",
+        }
+    }
+}
+
+pub const RENDERERS: [Renderer; 2] = [Renderer::Dom, Renderer::Canvas];
+
+/// What a Ctrl (wall) or Shift (open) stroke draws; the one-way tools paint arrows instead of walls
+/// and the portal tool links the cells a stroke starts and ends on.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Tool {
+    Freehand,
+    Line,
+    Rectangle,
+    FilledRectangle,
+    Fill,
+    OneWay(Direction),
+    Portal,
+}
+
+impl Tool {
+    pub fn from_value(value: &str) -> Option<Self> {
+        TOOLS.into_iter().find(|tool| tool.get_value() == value)
+    }
+
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            Tool::Freehand => "freehand",
+            Tool::Line => "line",
+            Tool::Rectangle => "rectangle",
+            Tool::FilledRectangle => "filled-rectangle",
+            Tool::Fill => "fill",
+            Tool::OneWay(Direction::Left) => "one-way-left",
+            Tool::OneWay(Direction::Right) => "one-way-right",
+            Tool::OneWay(Direction::Up) => "one-way-up",
+            Tool::OneWay(Direction::Down) => "one-way-down",
+            Tool::Portal => "portal",
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Tool::Freehand => "freehand",
+            Tool::Line => "line",
+            Tool::Rectangle => "rectangle",
+            Tool::FilledRectangle => "filled rectangle",
+            Tool::Fill => "bucket fill",
+            Tool::OneWay(Direction::Left) => "one-way ←",
+            Tool::OneWay(Direction::Right) => "one-way →",
+            Tool::OneWay(Direction::Up) => "one-way ↑",
+            Tool::OneWay(Direction::Down) => "one-way ↓",
+            Tool::Portal => "portal pair",
+        }
+    }
+}
+
+pub const TOOLS: [Tool; 10] = [
+    Tool::Freehand,
+    Tool::Line,
+    Tool::Rectangle,
+    Tool::FilledRectangle,
+    Tool::Fill,
+    Tool::OneWay(Direction::Left),
+    Tool::OneWay(Direction::Right),
+    Tool::OneWay(Direction::Up),
+    Tool::OneWay(Direction::Down),
+    Tool::Portal,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BrushShape {
+    Square,
+    Circle,
+}
+
+impl BrushShape {
+    pub fn from_value(value: &str) -> Option<Self> {
+        BRUSH_SHAPES.into_iter().find(|shape| shape.get_value() == value)
+    }
+
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            BrushShape::Square => "square",
+            BrushShape::Circle => "circle",
+        }
+    }
+}
+
+pub const BRUSH_SHAPES: [BrushShape; 2] = [BrushShape::Square, BrushShape::Circle];
+
+/// How many cells across every painted cell of a stroke grows to; always odd so the pointer stays in the middle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Brush {
+    pub size: usize,
+    pub shape: BrushShape,
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Brush {
+            size: 1,
+            shape: BrushShape::Square,
+        }
+    }
+}
+
+pub const BRUSH_SIZES: [usize; 5] = [1, 3, 5, 7, 9];
+
+/// Everything a stroke draws with, as chosen when it is made.
+#[derive(Clone, Copy, PartialEq)]
+pub struct DrawingOptions {
+    pub tool: Tool,
+    pub brush: Brush,
+    /// What a jump costs between the portals the portal tool links.
+    pub portal_cost: usize,
+}
+
 pub const CLICK: &str = "click";
 pub const CONTEXT_MENU: &str = "contextmenu";
+pub const MOUSE_DOWN: &str = "mousedown";
 pub const MOUSE_OVER: &str = "mouseover";
+pub const MOUSE_MOVE: &str = "mousemove";
@@ -0,0 +1,352 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gloo::render::{request_animation_frame, AnimationFrame};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use yew::prelude::*;
+
+use crate::export;
+
+use super::{GridDimensions, GridNode, Nodes};
+
+/// The canvas is drawn at most this many pixels across and scaled by CSS to the board's size.
+const MAX_CANVAS_PIXELS: usize = 2000;
+const MAX_CELL_PIXELS: usize = 16;
+/// Cells at least this big get a 1px gap around them, like the DOM grid.
+const MIN_CELL_PIXELS_WITH_GAP: usize = 8;
+
+/// Changed cells next to each other in one row that now share a color, drawn together.
+#[derive(Debug, PartialEq)]
+pub struct DirtyRun {
+    pub row: usize,
+    pub start_column: usize,
+    pub length: usize,
+    pub color_index: u8,
+}
+
+pub fn get_cell_pixels(dimensions: GridDimensions) -> usize {
+    (MAX_CANVAS_PIXELS / dimensions.columns.max(dimensions.rows).max(1)).clamp(1, MAX_CELL_PIXELS)
+}
+
+/// Each cell's index into `export::get_palette`, which starts with the background.
+pub fn get_color_indices(nodes: &[GridNode]) -> Vec<u8> {
+    nodes.iter().map(|grid_node| export::get_color_index(grid_node) as u8 + 1).collect()
+}
+
+/// The cells whose color differs from the last drawn frame; without one, every cell is dirty.
+pub fn get_dirty_runs(drawn_frame: Option<&[u8]>, frame: &[u8], columns: usize) -> Vec<DirtyRun> {
+    let mut runs: Vec<DirtyRun> = Vec::new();
+
+    frame
+        .iter()
+        .enumerate()
+        .filter(|&(node_id, color_index)| drawn_frame.is_none_or(|drawn_frame| drawn_frame[node_id] != *color_index))
+        .for_each(|(node_id, &color_index)| {
+            let (row, column) = (node_id / columns, node_id % columns);
+            match runs.last_mut() {
+                Some(run) if run.row == row && run.start_column + run.length == column && run.color_index == color_index => {
+                    run.length += 1;
+                }
+                _ => runs.push(DirtyRun { row, start_column: column, length: 1, color_index }),
+            }
+        });
+
+    runs
+}
+
+thread_local! {
+    /// Every mounted canvas, painted together on one animation frame. The model has no change events
+    /// of its own, so `utils::set_node_status`, which every edit and search step goes through, asks
+    /// for the frame.
+    static SHARED_FRAME: RefCell<SharedFrame> = RefCell::new(SharedFrame::default());
+}
+
+/// One canvas a board is drawn on, at its own scale.
+struct CanvasTarget {
+    id: usize,
+    canvas_ref: NodeRef,
+    cell_pixels: usize,
+    is_drawn: bool,
+}
+
+impl CanvasTarget {
+    /// Fills the runs in, telling whether the canvas was there to draw on.
+    fn draw(&self, runs: &[DirtyRun], fill_styles: &[JsValue]) -> bool {
+        let Some(context) = self
+            .canvas_ref
+            .cast::<HtmlCanvasElement>()
+            .and_then(|canvas| canvas.get_context("2d").ok().flatten())
+            .and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok())
+        else {
+            return false;
+        };
+
+        let cell_pixels = self.cell_pixels;
+        let gap = if cell_pixels >= MIN_CELL_PIXELS_WITH_GAP { 1 } else { 0 };
+
+        runs.iter().for_each(|run| {
+            let x = (run.start_column * cell_pixels) as f64;
+            let y = (run.row * cell_pixels) as f64;
+            let size = cell_pixels as f64;
+
+            if gap == 0 {
+                context.set_fill_style(&fill_styles[run.color_index as usize]);
+                context.fill_rect(x, y, size * run.length as f64, size);
+                return;
+            }
+
+            context.set_fill_style(&fill_styles[0]);
+            context.fill_rect(x, y, size * run.length as f64, size);
+            context.set_fill_style(&fill_styles[run.color_index as usize]);
+            (0..run.length).for_each(|offset| {
+                context.fill_rect(x + (offset * cell_pixels + gap) as f64, y + gap as f64, size - gap as f64, size - gap as f64);
+            });
+        });
+
+        true
+    }
+}
+
+/// Repaints only what changed in one board since the last frame, on every canvas showing it, so the
+/// grid and the minimap share one diff.
+struct CanvasPainter {
+    nodes: Nodes,
+    dimensions: GridDimensions,
+    targets: Vec<CanvasTarget>,
+    drawn_frame: Option<Vec<u8>>,
+    fill_styles: Vec<JsValue>,
+}
+
+impl CanvasPainter {
+    fn new(nodes: Nodes, dimensions: GridDimensions) -> Self {
+        let fill_styles = export::get_palette()
+            .chunks(3)
+            .map(|rgb| JsValue::from_str(&format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])))
+            .collect();
+
+        CanvasPainter {
+            nodes,
+            dimensions,
+            targets: Vec::new(),
+            drawn_frame: None,
+            fill_styles,
+        }
+    }
+
+    fn paint(&mut self) {
+        let frame = get_color_indices(&self.nodes.borrow());
+        let columns = self.dimensions.columns;
+        let dirty_runs = get_dirty_runs(self.drawn_frame.as_deref(), &frame, columns);
+        // a canvas added since the last frame is still blank, so it gets every cell
+        let all_runs = self
+            .targets
+            .iter()
+            .any(|target| !target.is_drawn)
+            .then(|| get_dirty_runs(None, &frame, columns));
+
+        self.targets.iter_mut().for_each(|target| {
+            let runs = match (&all_runs, target.is_drawn) {
+                (Some(all_runs), false) => all_runs,
+                _ => &dirty_runs,
+            };
+            target.is_drawn = target.draw(runs, &self.fill_styles);
+        });
+
+        self.drawn_frame = Some(frame);
+    }
+}
+
+#[derive(Default)]
+struct SharedFrame {
+    canvas_painters: Vec<CanvasPainter>,
+    /// Layers drawn over the board, painted after its cells.
+    overlay_painters: Vec<(usize, Box<dyn FnMut()>)>,
+    next_id: usize,
+    animation_frame: Option<AnimationFrame>,
+    is_pending: bool,
+}
+
+impl SharedFrame {
+    fn get_next_id(&mut self) -> usize {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn is_empty(&self) -> bool {
+        self.canvas_painters.is_empty() && self.overlay_painters.is_empty()
+    }
+
+    fn remove(&mut self, id: usize) {
+        self.canvas_painters.iter_mut().for_each(|painter| painter.targets.retain(|target| target.id != id));
+        self.canvas_painters.retain(|painter| !painter.targets.is_empty());
+        self.overlay_painters.retain(|(overlay_id, _)| *overlay_id != id);
+
+        if self.is_empty() {
+            self.animation_frame = None;
+            self.is_pending = false;
+        }
+    }
+
+    fn paint(&mut self) {
+        self.is_pending = false;
+        self.canvas_painters.iter_mut().for_each(CanvasPainter::paint);
+        self.overlay_painters.iter_mut().for_each(|(_, paint)| paint());
+    }
+}
+
+/// Keeps a canvas or overlay on the shared frame until dropped.
+pub struct FrameRegistration {
+    id: usize,
+}
+
+impl Drop for FrameRegistration {
+    fn drop(&mut self) {
+        SHARED_FRAME.with(|shared_frame| shared_frame.borrow_mut().remove(self.id));
+    }
+}
+
+/// Asks for one repaint of everything mounted; further requests before it runs are folded into it.
+pub fn request_frame() {
+    SHARED_FRAME.with(|shared_frame| {
+        let mut shared_frame = shared_frame.borrow_mut();
+        if shared_frame.is_pending || shared_frame.is_empty() {
+            return;
+        }
+
+        shared_frame.is_pending = true;
+        // the finished frame's handle is only replaced here, never dropped from inside its own callback
+        shared_frame.animation_frame = Some(request_animation_frame(|_| {
+            SHARED_FRAME.with(|shared_frame| shared_frame.borrow_mut().paint());
+        }));
+    });
+}
+
+/// Draws the board on the canvas from the next frame on; canvases showing the same board at the
+/// same size share one painter.
+pub fn add_canvas(nodes: Nodes, dimensions: GridDimensions, cell_pixels: usize, canvas_ref: NodeRef) -> FrameRegistration {
+    let registration = SHARED_FRAME.with(|shared_frame| {
+        let mut shared_frame = shared_frame.borrow_mut();
+        let id = shared_frame.get_next_id();
+        let target = CanvasTarget { id, canvas_ref, cell_pixels, is_drawn: false };
+
+        let painter_index = shared_frame
+            .canvas_painters
+            .iter()
+            .position(|painter| Rc::ptr_eq(&painter.nodes, &nodes) && painter.dimensions == dimensions)
+            .unwrap_or_else(|| {
+                shared_frame.canvas_painters.push(CanvasPainter::new(nodes, dimensions));
+                shared_frame.canvas_painters.len() - 1
+            });
+        shared_frame.canvas_painters[painter_index].targets.push(target);
+
+        FrameRegistration { id }
+    });
+
+    request_frame();
+    registration
+}
+
+/// Runs `paint` on every frame the board changed in, after the board's cells are drawn.
+pub fn add_overlay(paint: impl FnMut() + 'static) -> FrameRegistration {
+    let registration = SHARED_FRAME.with(|shared_frame| {
+        let mut shared_frame = shared_frame.borrow_mut();
+        let id = shared_frame.get_next_id();
+        shared_frame.overlay_painters.push((id, Box::new(paint)));
+
+        FrameRegistration { id }
+    });
+
+    request_frame();
+    registration
+}
+
+#[derive(Properties)]
+pub struct CanvasGridProps {
+    pub nodes: Nodes,
+    pub dimensions: GridDimensions,
+    pub on_mouse_action: Callback<MouseEvent>,
+}
+
+impl PartialEq for CanvasGridProps {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.nodes, &other.nodes)
+            && self.dimensions == other.dimensions
+            && self.on_mouse_action == other.on_mouse_action
+    }
+}
+
+/// Draws the whole board on one canvas, which keeps boards far beyond 50x50 responsive.
+#[function_component]
+pub fn CanvasGrid(props: &CanvasGridProps) -> Html {
+    let canvas_ref = use_node_ref();
+
+    {
+        let nodes = Rc::clone(&props.nodes);
+        let canvas_ref = canvas_ref.clone();
+
+        use_effect_with_deps(
+            move |(_, dimensions)| {
+                let registration = add_canvas(nodes, *dimensions, get_cell_pixels(*dimensions), canvas_ref);
+
+                move || drop(registration)
+            },
+            (Rc::as_ptr(&props.nodes) as usize, props.dimensions),
+        );
+    }
+
+    let GridDimensions { columns, rows } = props.dimensions;
+    let cell_pixels = get_cell_pixels(props.dimensions);
+    let size_class = if columns >= rows { "w-full" } else { "h-full" };
+
+    html! {
+        <canvas
+            ref={canvas_ref}
+            class={classes!("bg-gray-900", size_class)}
+            style={format!("aspect-ratio: {} / {}; image-rendering: pixelated;", columns, rows)}
+            width={(columns * cell_pixels).to_string()}
+            height={(rows * cell_pixels).to_string()}
+            onclick={&props.on_mouse_action}
+            oncontextmenu={&props.on_mouse_action}
+            onmousedown={&props.on_mouse_action}
+            onmousemove={&props.on_mouse_action}
+            />
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_shrink_to_fit_large_boards() {
+        assert_eq!(get_cell_pixels(GridDimensions::square(50)), MAX_CELL_PIXELS);
+        assert_eq!(get_cell_pixels(GridDimensions::square(500)), 4);
+        assert_eq!(get_cell_pixels(GridDimensions { columns: 4000, rows: 10 }), 1);
+    }
+
+    #[test]
+    fn dirty_runs_merge_neighbors_of_one_color_within_a_row() {
+        let drawn_frame = vec![1, 1, 1, 1, 1, 1, 1, 1];
+        let frame = vec![1, 2, 2, 3, 2, 1, 1, 2];
+
+        assert_eq!(
+            get_dirty_runs(Some(&drawn_frame), &frame, 4),
+            vec![
+                DirtyRun { row: 0, start_column: 1, length: 2, color_index: 2 },
+                DirtyRun { row: 0, start_column: 3, length: 1, color_index: 3 },
+                DirtyRun { row: 1, start_column: 0, length: 1, color_index: 2 },
+                DirtyRun { row: 1, start_column: 3, length: 1, color_index: 2 },
+            ]
+        );
+        assert!(get_dirty_runs(Some(&frame), &frame, 4).is_empty());
+    }
+
+    #[test]
+    fn first_frame_draws_every_row() {
+        let runs = get_dirty_runs(None, &[1, 1, 1, 1, 1, 1], 3);
+
+        assert_eq!(runs.len(), 2);
+        assert!(runs.iter().all(|run| run.length == 3));
+    }
+}
@@ -0,0 +1,227 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::dijkstras::{Algorithm, DijkstrasTraversal, ALGORITHMS};
+use crate::utils;
+use crate::visualizer::{self, AnimationSpeed, LockstepRun};
+use crate::NodeStatus;
+
+use super::{Direction, GridDimensions, GridNode, Nodes, Portal};
+
+/// The editable part of a board (walls, weights, one-way arrows, portals and endpoints) without any search results on it.
+#[derive(Clone, PartialEq)]
+pub struct BoardLayout {
+    pub dimensions: GridDimensions,
+    pub node_statuses: Vec<NodeStatus>,
+    pub weights: Vec<usize>,
+    pub directions: Vec<Option<Direction>>,
+    pub portals: Vec<Option<Portal>>,
+    pub start_node_id: usize,
+    pub end_node_id: usize,
+}
+
+impl BoardLayout {
+    pub fn capture(nodes: &[GridNode], dimensions: GridDimensions, start_node_id: usize, end_node_id: usize) -> Self {
+        let node_statuses = nodes
+            .iter()
+            .map(|node| match node.node_status {
+                NodeStatus::Visited | NodeStatus::Frontier | NodeStatus::Path => NodeStatus::On,
+                ref node_status => node_status.clone(),
+            })
+            .collect();
+
+        BoardLayout {
+            dimensions,
+            node_statuses,
+            weights: nodes.iter().map(|node| node.weight).collect(),
+            directions: nodes.iter().map(|node| node.direction).collect(),
+            portals: nodes.iter().map(|node| node.portal).collect(),
+            start_node_id,
+            end_node_id,
+        }
+    }
+
+    pub fn create_nodes(&self) -> Nodes {
+        let grid_nodes = self
+            .node_statuses
+            .iter()
+            .zip(self.weights.iter())
+            .zip(self.directions.iter())
+            .zip(self.portals.iter())
+            .map(|(((node_status, &weight), &direction), &portal)| GridNode {
+                direction,
+                portal,
+                ..GridNode::build_preview(node_status.clone(), weight)
+            })
+            .collect();
+
+        Rc::new(RefCell::new(grid_nodes))
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct ComparisonRow {
+    algorithm: Algorithm,
+    cost: Option<usize>,
+    visited_count: usize,
+    time_ms: f64,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ComparisonProps {
+    pub layout: BoardLayout,
+    pub speed: AnimationSpeed,
+    pub on_close: Callback<MouseEvent>,
+}
+
+#[function_component]
+pub fn Comparison(props: &ComparisonProps) -> Html {
+    let selected_algorithms = use_mut_ref(|| vec![Algorithm::Dijkstra, Algorithm::AStar]);
+    let boards = use_state(Vec::<(Algorithm, Nodes)>::new);
+    let rows = use_state(Vec::<ComparisonRow>::new);
+    let pending_runs = use_mut_ref(|| Option::<Vec<LockstepRun>>::None);
+    let is_animating = use_mut_ref(|| false);
+    let run_count = use_mut_ref(|| 0_usize);
+
+    {
+        let pending_runs = Rc::clone(&pending_runs);
+        let is_animating = Rc::clone(&is_animating);
+        let speed = props.speed;
+
+        // the boards must be mounted before their cells can be painted
+        use_effect(move || {
+            if let Some(runs) = pending_runs.borrow_mut().take() {
+                let end_of_visualization_callback = move || {
+                    *is_animating.borrow_mut() = false;
+                };
+                visualizer::visualize_lockstep(runs, speed, end_of_visualization_callback);
+            }
+        });
+    }
+
+    let handle_toggle_algorithm = |algorithm: Algorithm| {
+        let selected_algorithms = Rc::clone(&selected_algorithms);
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            let mut selected_algorithms = selected_algorithms.borrow_mut();
+            selected_algorithms.retain(|&selected| selected != algorithm);
+            if input.checked() {
+                selected_algorithms.push(algorithm);
+                selected_algorithms.sort_by_key(|selected| ALGORITHMS.iter().position(|a| a == selected));
+            }
+        })
+    };
+
+    let handle_run = {
+        let layout = props.layout.clone();
+        let selected_algorithms = Rc::clone(&selected_algorithms);
+        let boards = boards.clone();
+        let rows = rows.clone();
+        let pending_runs = Rc::clone(&pending_runs);
+        let is_animating = Rc::clone(&is_animating);
+        let run_count = Rc::clone(&run_count);
+
+        Callback::from(move |_| {
+            if *is_animating.borrow() || selected_algorithms.borrow().len() < 2 {
+                return;
+            }
+            *is_animating.borrow_mut() = true;
+            *run_count.borrow_mut() += 1;
+
+            let mut new_boards = Vec::new();
+            let mut new_rows = Vec::new();
+            let mut runs = Vec::new();
+
+            selected_algorithms.borrow().iter().for_each(|&algorithm| {
+                let nodes = layout.create_nodes();
+
+                let started_at = utils::now_ms();
+                let traversal = DijkstrasTraversal::run_with_algorithm(
+                    &nodes.borrow(),
+                    layout.dimensions,
+                    layout.start_node_id,
+                    layout.end_node_id,
+                    algorithm,
+                );
+                let time_ms = utils::now_ms() - started_at;
+
+                new_rows.push(ComparisonRow {
+                    algorithm,
+                    cost: traversal.get_cost(),
+                    visited_count: traversal.get_visited_count(),
+                    time_ms,
+                });
+                runs.push(LockstepRun {
+                    nodes: Rc::clone(&nodes),
+                    steps: traversal.get_steps(),
+                    path: traversal.get_path(),
+                });
+                new_boards.push((algorithm, nodes));
+            });
+
+            *pending_runs.borrow_mut() = Some(runs);
+            boards.set(new_boards);
+            rows.set(new_rows);
+        })
+    };
+
+    let board_style = props.layout.dimensions.get_grid_style();
+
+    html! {
+        <div class="flex flex-col gap-8 items-center">
+            <div class="flex gap-6 items-center text-zinc-400">
+                {ALGORITHMS.iter().map(|&algorithm| html! {
+                    <label class="flex items-center gap-2">
+                        <input
+                            type="checkbox"
+                            checked={selected_algorithms.borrow().contains(&algorithm)}
+                            onchange={handle_toggle_algorithm(algorithm)}
+                            />
+                        {algorithm.get_name()}
+                    </label>
+                }).collect::<Html>()}
+                <button class="text-white border-2 border-green-600 p-2 rounded-md hover:bg-green-600 hover:text-black" onclick={handle_run}>{"Run comparison"}</button>
+                <button class="text-white border-2 border-zinc-600 p-2 rounded-md hover:bg-zinc-600 hover:text-black" onclick={&props.on_close}>{"Back to editor"}</button>
+            </div>
+
+            <div class="flex gap-6">
+                // keyed by run so every run starts from freshly mounted, unpainted cells
+                {boards.iter().map(|(algorithm, nodes)| html! {
+                    <div key={format!("{}-{}", *run_count.borrow(), algorithm.get_value())} class="flex flex-col gap-2 items-center text-white">
+                        <p>{algorithm.get_name()}</p>
+                        <div class="grid gap-0.25 bg-gray-900 w-400 border-2 border-teal-700" style={board_style.clone()}>
+                            {nodes.borrow().iter().map(|GridNode {node, ..}| node.clone()).collect::<Html>()}
+                        </div>
+                    </div>
+                }).collect::<Html>()}
+            </div>
+
+            if !rows.is_empty() {
+                <table class="text-zinc-300 border-2 border-zinc-700">
+                    <thead>
+                        <tr class="text-zinc-500">
+                            <th class="px-4 py-1 text-left">{"algorithm"}</th>
+                            <th class="px-4 py-1 text-right">{"cost"}</th>
+                            <th class="px-4 py-1 text-right">{"visited"}</th>
+                            <th class="px-4 py-1 text-right">{"time (ms)"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {rows.iter().map(|row| html! {
+                            <tr>
+                                <td class="px-4 py-1">{row.algorithm.get_name()}</td>
+                                <td class="px-4 py-1 text-right">{row.cost.map_or("no path".to_string(), |cost| cost.to_string())}</td>
+                                <td class="px-4 py-1 text-right">{row.visited_count}</td>
+                                <td class="px-4 py-1 text-right">{format!("{:.2}", row.time_ms)}</td>
+                            </tr>
+                        }).collect::<Html>()}
+                    </tbody>
+                </table>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,102 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::fog::{FogRun, DEFAULT_SENSOR_RADIUS, MAX_SENSOR_RADIUS};
+
+/// How far a walk through the fog has got, as of the step on screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FogProgress {
+    pub steps_taken: usize,
+    pub travelled_cost: usize,
+    pub replans: usize,
+    pub optimal_cost: Option<usize>,
+    pub is_finished: bool,
+    pub is_goal_reached: bool,
+}
+
+impl FogProgress {
+    pub fn build(run: &FogRun, step_index: usize) -> Self {
+        let steps_so_far = &run.steps[..=step_index];
+        let is_finished = step_index + 1 == run.steps.len();
+
+        FogProgress {
+            steps_taken: step_index,
+            travelled_cost: steps_so_far.last().map_or(0, |step| step.travelled_cost),
+            replans: steps_so_far.iter().filter(|step| step.is_replan).count(),
+            optimal_cost: run.optimal_cost,
+            is_finished,
+            is_goal_reached: is_finished && run.is_goal_reached,
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct FogPanelProps {
+    pub progress: Option<FogProgress>,
+    /// Emits the chosen sensor radius.
+    pub on_explore: Callback<usize>,
+}
+
+/// Sends an agent that only sees the cells around it to the end node and keeps score against
+/// the cost it would have paid knowing the whole board.
+#[function_component]
+pub fn FogPanel(props: &FogPanelProps) -> Html {
+    let sensor_radius = use_mut_ref(|| DEFAULT_SENSOR_RADIUS);
+
+    let handle_change_sensor_radius = {
+        let sensor_radius = sensor_radius.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(radius) = input.value().parse::<usize>() {
+                *sensor_radius.borrow_mut() = radius.clamp(1, MAX_SENSOR_RADIUS);
+            }
+        })
+    };
+
+    let handle_explore = {
+        let on_explore = props.on_explore.clone();
+
+        Callback::from(move |_| on_explore.emit(*sensor_radius.borrow()))
+    };
+
+    html! {
+        <div class="flex flex-col gap-2 w-56 p-3 rounded-md border-2 border-zinc-700 text-zinc-400">
+            <p>{"Fog of war"}</p>
+            <label class="flex items-center gap-2 text-sm">{"Sensor radius"}
+                <input
+                    class="w-14 bg-gray-800 text-white p-1 rounded-md"
+                    type="number"
+                    min="1"
+                    max={MAX_SENSOR_RADIUS.to_string()}
+                    value={DEFAULT_SENSOR_RADIUS.to_string()}
+                    onchange={handle_change_sensor_radius}
+                    />
+            </label>
+            <button class="text-white border-2 border-fuchsia-500 p-1 rounded-md hover:bg-fuchsia-500 hover:text-black" onclick={handle_explore}>
+                {"Explore in fog"}
+            </button>
+            if let Some(progress) = props.progress {
+                <p class="text-sm">{"Steps: "}<span class="text-white">{progress.steps_taken}</span></p>
+                <p class="text-sm">{"Replans: "}<span class="text-white">{progress.replans}</span></p>
+                <p class="text-sm">
+                    {"Travelled: "}<span class="text-white">{progress.travelled_cost}</span>
+                    {" / optimal: "}<span class="text-white">{progress.optimal_cost.map_or("no path".to_string(), |cost| cost.to_string())}</span>
+                </p>
+                if progress.is_goal_reached {
+                    if let Some(optimal_cost) = progress.optimal_cost.filter(|&cost| cost > 0) {
+                        <p class="text-sm">
+                            {"Detour: "}
+                            <span class="text-white">
+                                {format!("+{} ({:.0}%)", progress.travelled_cost - optimal_cost, (progress.travelled_cost - optimal_cost) as f64 / optimal_cost as f64 * 100.0)}
+                            </span>
+                        </p>
+                    }
+                } else if progress.is_finished {
+                    <p class="text-sm text-red-500">{"Gave up: every route it knows of is walled off."}</p>
+                }
+            }
+            <p class="text-xs text-zinc-500">{"Unseen cells are assumed open; the sensor sees through walls."}</p>
+        </div>
+    }
+}
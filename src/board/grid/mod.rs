@@ -0,0 +1,68 @@
+use std::rc::Rc;
+
+use web_sys::HtmlElement;
+use yew::prelude::*;
+
+use crate::utils;
+
+use super::{GridDimensions, GridNode, Nodes};
+
+#[derive(Properties)]
+pub struct GridProps {
+    pub nodes: Nodes,
+    pub dimensions: GridDimensions,
+    pub on_mouse_action: Callback<MouseEvent>,
+}
+
+impl PartialEq for GridProps {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.nodes, &other.nodes)
+            && self.dimensions == other.dimensions
+            && self.on_mouse_action == other.on_mouse_action
+    }
+}
+
+#[function_component]
+pub fn Grid(props: &GridProps) -> Html {
+    {
+        let nodes = Rc::clone(&props.nodes);
+
+        // cells are built with their first status, so catch up with anything painted while unmounted
+        use_effect_with_deps(
+            move |_| {
+                nodes.borrow().iter().for_each(|grid_node| {
+                    if let Some(node_ref) = grid_node.node_ref.cast::<HtmlElement>() {
+                        utils::set_node_color(&node_ref, grid_node.node_status.clone(), grid_node.weight);
+                    }
+                });
+            },
+            (),
+        );
+    }
+
+    let handle_drag_start = {
+        Callback::from(|event: DragEvent| {
+            event.prevent_default();
+        })
+    };
+
+    let GridDimensions { columns, rows } = props.dimensions;
+    // the longer side fills the viewport and the aspect ratio in the style sets the other one
+    let size_class = if columns >= rows { "w-full" } else { "h-full" };
+    // past a hundred cells across the gaps would eat most of the board
+    let gap_class = if columns.max(rows) > 100 { "gap-0" } else { "gap-0.25" };
+
+    html! {
+        <div
+           class={classes!("grid", gap_class, "bg-gray-900", size_class)}
+           style={props.dimensions.get_grid_style()}
+           onclick={&props.on_mouse_action}
+           oncontextmenu={&props.on_mouse_action}
+           onmousedown={&props.on_mouse_action}
+           onmouseover={&props.on_mouse_action}
+           ondragstart={&handle_drag_start}
+           >
+           {props.nodes.borrow().iter().map(|GridNode {node, ..}| node.clone()).collect::<Html>()}
+        </div>
+    }
+}
@@ -1,55 +1,71 @@
+use std::cell::RefMut;
 use std::rc::Rc;
 
-use wasm_bindgen::prelude::*;
-use web_sys::HtmlElement;
 use yew::prelude::*;
 
+use crate::drawing;
 use crate::utils::{self, ModifierKey};
+use crate::NodeStatus;
 
-use super::{Nodes, CurrentStartNode, CurrentEndNode};
+use super::{BoardGeometry, DrawingOptions, GridNode, Portal, Nodes, CurrentStartNode, CurrentEndNode, CurrentStroke, Tool};
 use utils::{Button, ButtonWithModifierKey, MouseAction};
 
 pub trait TouchSquare {
-    fn trigger_node(&self);
+    /// Applies the action to the board and tells whether any cell changed.
+    fn trigger_node(&self) -> bool;
 }
 
 pub struct GridState {
     nodes: Nodes,
     current_start_node_id: CurrentStartNode,
     current_end_node_id: CurrentEndNode,
+    current_stroke: CurrentStroke,
+    drawing_options: DrawingOptions,
+    geometry: BoardGeometry,
 }
 
 impl GridState {
-    pub fn new(nodes: Nodes, current_start_node_id: CurrentStartNode, current_end_node_id: CurrentEndNode) -> GridState {
+    pub fn new(
+        nodes: Nodes,
+        current_start_node_id: CurrentStartNode,
+        current_end_node_id: CurrentEndNode,
+        current_stroke: CurrentStroke,
+        drawing_options: DrawingOptions,
+        geometry: BoardGeometry,
+    ) -> GridState {
         GridState {
             nodes,
             current_start_node_id,
             current_end_node_id,
+            current_stroke,
+            drawing_options,
+            geometry,
         }
     }
 }
 
 pub struct GridAction {
-    html_element: HtmlElement,
     node_id: usize,
+    mouse_action: MouseAction,
     grid_state: GridState,
     pub button_with_modifier: ButtonWithModifierKey,
 }
 
 impl GridAction {
+   /// Works from the pointer position rather than the event target, so it holds for either
+   /// renderer and for any zoom or pan.
+   pub fn get_node_id(event: &MouseEvent, geometry: &BoardGeometry) -> Option<usize> {
+        geometry.get_node_id(event.client_x() as f64, event.client_y() as f64)
+   }
+
    pub fn new(event: &MouseEvent, mouse_action: MouseAction, grid_state: GridState) -> Option<Self> {
-        let html_element = event.target().and_then(|t| t.dyn_into::<HtmlElement>().ok())?;
-         
-        if html_element.id().is_empty() {
-            return None;
-        }
+        let node_id = Self::get_node_id(event, &grid_state.geometry)?;
 
         let button = match mouse_action {
-            MouseAction::Click => utils::get_click_button(&event),
-            MouseAction::Move => utils::get_move_button(&event),
+            MouseAction::Press | MouseAction::Click => utils::get_click_button(event),
+            MouseAction::Move => utils::get_move_button(event),
         };
-        let modifier_key = utils::get_modifier_key(&event);
-        let node_id = html_element.id().parse::<usize>().ok()?;
+        let modifier_key = utils::get_modifier_key(event);
 
         let button_with_modifier = match button {
             Button::Left => ButtonWithModifierKey::Left(modifier_key),
@@ -58,41 +74,173 @@ impl GridAction {
         };
 
         Some(Self {
-            html_element,
             node_id,
+            mouse_action,
             grid_state,
             button_with_modifier,
         })
     }
 }
 
-impl TouchSquare for GridAction {
-    fn trigger_node(&self) {
-        let mut nodes_borrow = self.grid_state.nodes.borrow_mut();
-        let node_status = &mut nodes_borrow[self.node_id].node_status;
-        if !utils::is_node_toggleable(node_status) {
-            return;
+impl GridAction {
+    /// The cells a wall or open stroke paints with this event, depending on the tool.
+    fn get_stroke_node_ids(&self, nodes: &[GridNode]) -> Vec<usize> {
+        let mut current_stroke = self.grid_state.current_stroke.borrow_mut();
+        let dimensions = self.grid_state.geometry.dimensions;
+
+        match self.mouse_action {
+            MouseAction::Press => {
+                *current_stroke = Some(self.node_id);
+                match self.grid_state.drawing_options.tool {
+                    Tool::Freehand | Tool::OneWay(_) => vec![self.node_id],
+                    _ => Vec::new(),
+                }
+            }
+            MouseAction::Move => match self.grid_state.drawing_options.tool {
+                // joins up with the last cell reached, since fast moves skip cells between events
+                Tool::Freehand | Tool::OneWay(_) => {
+                    let from = current_stroke.replace(self.node_id).unwrap_or(self.node_id);
+                    drawing::get_line(from, self.node_id, dimensions)
+                }
+                _ => {
+                    current_stroke.get_or_insert(self.node_id);
+                    Vec::new()
+                }
+            },
+            MouseAction::Click => {
+                let anchor = current_stroke.take().unwrap_or(self.node_id);
+                match self.grid_state.drawing_options.tool {
+                    Tool::Freehand | Tool::OneWay(_) => vec![self.node_id],
+                    Tool::Line => drawing::get_line(anchor, self.node_id, dimensions),
+                    Tool::Rectangle => drawing::get_rectangle(anchor, self.node_id, dimensions, false),
+                    Tool::FilledRectangle => drawing::get_rectangle(anchor, self.node_id, dimensions, true),
+                    // the two ends of a portal pair
+                    Tool::Portal => vec![anchor, self.node_id],
+                    Tool::Fill => {
+                        // a solved board is searched again after the edit, so its searched cells fill as open ones
+                        let node_statuses: Vec<NodeStatus> = nodes
+                            .iter()
+                            .map(|grid_node| match utils::is_node_searched(&grid_node.node_status) {
+                                true => NodeStatus::On,
+                                false => grid_node.node_status.clone(),
+                            })
+                            .collect();
+                        drawing::get_fill_region(&node_statuses, dimensions, self.node_id)
+                    }
+                }
+            }
+        }
+    }
+
+    fn move_endpoint(&self, mut nodes_borrow: RefMut<Vec<GridNode>>) -> bool {
+        if !is_node_editable(&nodes_borrow[self.node_id].node_status) {
+            return false;
         }
         let current_start_node_id = Rc::clone(&self.grid_state.current_start_node_id);
         let current_end_node_id = Rc::clone(&self.grid_state.current_end_node_id);
 
-
         match self.button_with_modifier {
-            ButtonWithModifierKey::Left(ModifierKey::Ctrl) => {
-                utils::set_node_off(self.html_element.clone(), node_status);
-            }
-            ButtonWithModifierKey::Left(ModifierKey::Shift) => {
-                utils::set_node_on(self.html_element.clone(), node_status);
-            }
             ButtonWithModifierKey::Left(ModifierKey::None) => {
+                utils::clear_search_marks(&mut nodes_borrow[self.node_id]);
                 utils::set_start_node(nodes_borrow, self.node_id, current_start_node_id);
+                true
             }
             ButtonWithModifierKey::Right() => {
+                utils::clear_search_marks(&mut nodes_borrow[self.node_id]);
                 utils::set_end_node(nodes_borrow, self.node_id, current_end_node_id);
+                true
             }
-            ButtonWithModifierKey::Other() => {
-                return;
+            _ => false,
+        }
+    }
+}
+
+/// Edits land on open cells and walls, and on the cells a finished search painted over open ones.
+fn is_node_editable(node_status: &NodeStatus) -> bool {
+    utils::is_node_toggleable(node_status) || utils::is_node_searched(node_status)
+}
+
+/// Breaks up the pair a cell belongs to, so neither end is left pointing at the other.
+fn unlink_portal(nodes: &mut [GridNode], node_id: usize) {
+    if let Some(portal) = nodes[node_id].portal.take() {
+        nodes[portal.partner].portal = None;
+    }
+}
+
+/// Turns two open cells into a portal pair, taking both out of any pair they were in. Tells
+/// whether the pair is new.
+fn link_portals(nodes: &mut [GridNode], first: usize, second: usize, cost: usize) -> bool {
+    if first == second || ![first, second].iter().all(|&node_id| is_node_editable(&nodes[node_id].node_status)) {
+        return false;
+    }
+    if nodes[first].portal == Some(Portal { partner: second, cost }) {
+        return false;
+    }
+
+    [(first, second), (second, first)].into_iter().for_each(|(node_id, partner)| {
+        unlink_portal(nodes, node_id);
+        utils::clear_search_marks(&mut nodes[node_id]);
+        utils::set_node_on(&mut nodes[node_id]);
+        nodes[node_id].portal = Some(Portal { partner, cost });
+    });
+    true
+}
+
+impl TouchSquare for GridAction {
+    fn trigger_node(&self) -> bool {
+        let mut nodes_borrow = self.grid_state.nodes.borrow_mut();
+        let DrawingOptions { tool, brush, portal_cost } = self.grid_state.drawing_options;
+
+        // one-way cells stay open, so the arrow tools never draw walls
+        let (node_status, direction) = match (&self.button_with_modifier, tool) {
+            (ButtonWithModifierKey::Left(ModifierKey::Ctrl), Tool::OneWay(direction)) => (NodeStatus::On, Some(direction)),
+            (ButtonWithModifierKey::Left(ModifierKey::Ctrl), _) => (NodeStatus::Off, None),
+            (ButtonWithModifierKey::Left(ModifierKey::Shift), _) => (NodeStatus::On, None),
+            _ => {
+                // anything but a wall or open stroke ends the one in progress
+                *self.grid_state.current_stroke.borrow_mut() = None;
+                return self.move_endpoint(nodes_borrow);
             }
+        };
+
+        let dimensions = self.grid_state.geometry.dimensions;
+        let mut stroke_node_ids = self.get_stroke_node_ids(&nodes_borrow);
+        if let (Tool::Portal, ButtonWithModifierKey::Left(ModifierKey::Ctrl)) = (tool, &self.button_with_modifier) {
+            return match stroke_node_ids[..] {
+                [first, second] => link_portals(&mut nodes_borrow, first, second, portal_cost),
+                _ => false,
+            };
+        }
+
+        // a fill already covers its whole region and a portal stroke only its two ends, so the brush
+        // would only spill over them
+        if !matches!(tool, Tool::Fill | Tool::Portal) && brush.size > 1 {
+            stroke_node_ids = stroke_node_ids
+                .into_iter()
+                .flat_map(|node_id| drawing::get_brush_cells(node_id, brush, dimensions))
+                .collect();
         }
+
+        // start and end are never painted over, however wide the brush, and cells that already
+        // look the way the stroke would leave them are not touched
+        let mut is_changed = false;
+        stroke_node_ids.into_iter().for_each(|node_id| {
+            let grid_node = &mut nodes_borrow[node_id];
+            if !is_node_editable(&grid_node.node_status) {
+                return;
+            }
+            let is_open = grid_node.node_status == NodeStatus::On || utils::is_node_searched(&grid_node.node_status);
+            let is_same_status = is_open == (node_status == NodeStatus::On);
+            if is_same_status && grid_node.direction == direction && grid_node.portal.is_none() {
+                return;
+            }
+
+            utils::clear_search_marks(grid_node);
+            utils::paint_node(grid_node, node_status.clone());
+            grid_node.direction = direction;
+            unlink_portal(&mut nodes_borrow, node_id);
+            is_changed = true;
+        });
+        is_changed
     }
-}
\ No newline at end of file
+}
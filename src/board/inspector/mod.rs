@@ -0,0 +1,150 @@
+use yew::prelude::*;
+
+use crate::dijkstras::ShortestPathTree;
+use crate::NodeStatus;
+
+use super::{Direction, GridNode};
+
+#[derive(Clone, PartialEq)]
+pub struct CellInfo {
+    pub node_id: usize,
+    pub row: usize,
+    pub col: usize,
+    pub node_status: NodeStatus,
+    pub weight: usize,
+    pub direction: Option<Direction>,
+    /// The partner's row and column and the jump's cost, on portal cells.
+    pub portal: Option<((usize, usize), usize)>,
+    pub distance: Option<usize>,
+    pub is_final: bool,
+    pub predecessor: Option<(usize, usize)>,
+}
+
+impl CellInfo {
+    pub fn build(
+        node_id: usize,
+        grid_node: &GridNode,
+        columns: usize,
+        last_run: Option<&ShortestPathTree>,
+    ) -> Self {
+        let distance = last_run.map(|tree| tree.distances[node_id]);
+        let is_final = last_run.is_some_and(|tree| tree.is_settled(node_id));
+        let predecessor = last_run
+            .and_then(|tree| tree.previous[node_id])
+            .map(|previous_node_id| (previous_node_id / columns, previous_node_id % columns));
+
+        CellInfo {
+            node_id,
+            row: node_id / columns,
+            col: node_id % columns,
+            node_status: grid_node.node_status.clone(),
+            weight: grid_node.weight,
+            direction: grid_node.direction,
+            portal: grid_node.portal.map(|portal| ((portal.partner / columns, portal.partner % columns), portal.cost)),
+            distance,
+            is_final,
+            predecessor,
+        }
+    }
+
+    pub fn get_distance_label(&self) -> String {
+        match self.distance {
+            None => "no run yet".to_string(),
+            Some(usize::MAX) => "unreached".to_string(),
+            Some(distance) if self.is_final => distance.to_string(),
+            Some(distance) => format!("{} (tentative)", distance),
+        }
+    }
+}
+
+pub fn get_status_label(node_status: &NodeStatus) -> &'static str {
+    match node_status {
+        NodeStatus::On => "open",
+        NodeStatus::Off => "wall",
+        NodeStatus::Start => "start",
+        NodeStatus::End => "end",
+        NodeStatus::Path => "path",
+        NodeStatus::Visited => "visited",
+        NodeStatus::Frontier => "frontier",
+        NodeStatus::Unknown => "unknown",
+        NodeStatus::Discovered => "discovered wall",
+        NodeStatus::Agent => "agent",
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct InspectorProps {
+    pub cell: Option<CellInfo>,
+    pub is_tracing: bool,
+    pub on_trace_path: Callback<MouseEvent>,
+}
+
+#[function_component]
+pub fn Inspector(props: &InspectorProps) -> Html {
+    let Some(cell) = props.cell.as_ref() else {
+        return html! {
+            <div class="w-56 p-3 rounded-md border-2 border-zinc-700 text-zinc-500">
+                {"Hover a cell to inspect it"}
+            </div>
+        };
+    };
+
+    let predecessor = match cell.predecessor {
+        Some((row, col)) => format!("({}, {})", row, col),
+        None => "none".to_string(),
+    };
+    let trace_label = if props.is_tracing { "Hide path to start" } else { "Trace path to start" };
+
+    html! {
+        <div class="flex flex-col gap-1 w-56 p-3 rounded-md border-2 border-zinc-700 text-zinc-400">
+            <p>{"Cell: "}<span class="text-white">{format!("({}, {})", cell.row, cell.col)}</span></p>
+            <p>{"Status: "}<span class="text-white">{get_status_label(&cell.node_status)}</span></p>
+            <p>{"Weight: "}<span class="text-white">{cell.weight}</span></p>
+            if let Some(direction) = cell.direction {
+                <p>{"One-way: "}<span class="text-white">{direction.get_arrow()}</span></p>
+            }
+            if let Some(((row, col), cost)) = cell.portal {
+                <p>{"Portal to: "}<span class="text-white">{format!("({}, {})", row, col)}</span>{", cost "}<span class="text-white">{cost}</span></p>
+            }
+            <p>{"Distance: "}<span class="text-white">{cell.get_distance_label()}</span></p>
+            <p>{"Predecessor: "}<span class="text-white">{predecessor}</span></p>
+            <button
+                class="mt-2 text-white border-2 border-fuchsia-700 p-1 rounded-md hover:bg-fuchsia-700 hover:text-black disabled:opacity-40"
+                disabled={cell.predecessor.is_none() && !props.is_tracing}
+                onclick={&props.on_trace_path}
+                >
+                {trace_label}
+            </button>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests_common;
+
+    #[test]
+    fn cell_info_reports_last_run_state() {
+        let mock_grid = tests_common::get_mock_grid();
+        let mock_nodes = tests_common::get_mock_nodes(mock_grid);
+        let tree = ShortestPathTree {
+            distances: vec![usize::MAX; 100],
+            previous: vec![None; 100],
+            settled_ordered: vec![0],
+        };
+        let mut tree_with_tentative = tree.clone();
+        tree_with_tentative.distances[12] = 4;
+        tree_with_tentative.previous[12] = Some(11);
+
+        let without_run = CellInfo::build(12, &mock_nodes[12], 10, None);
+        let tentative = CellInfo::build(12, &mock_nodes[12], 10, Some(&tree_with_tentative));
+        let unreached = CellInfo::build(12, &mock_nodes[12], 10, Some(&tree));
+
+        assert_eq!((without_run.row, without_run.col), (1, 2));
+        assert_eq!(without_run.get_distance_label(), "no run yet");
+        assert_eq!(tentative.get_distance_label(), "4 (tentative)");
+        assert_eq!(tentative.predecessor, Some((1, 1)));
+        assert_eq!(unreached.get_distance_label(), "unreached");
+    }
+}
@@ -0,0 +1,197 @@
+use std::rc::Rc;
+
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use yew::prelude::*;
+
+use crate::NodeStatus;
+
+use super::canvas_grid;
+use super::{Direction, GridDimensions, GridNode, Nodes};
+
+/// Markers only need to be legible, so the layer is drawn at most this many pixels across.
+const MAX_LAYER_PIXELS: usize = 1600;
+const MAX_CELL_PIXELS: usize = 32;
+const ARROW_COLOR: &str = "rgba(255, 255, 255, 0.85)";
+/// Pairs take these in turn, so neighboring pairs can be told apart.
+const PORTAL_COLORS: [&str; 6] = ["#f472b6", "#facc15", "#a78bfa", "#34d399", "#fb923c", "#38bdf8"];
+const JUMP_COLOR: &str = "rgba(250, 204, 21, 0.9)";
+
+fn get_cell_pixels(dimensions: GridDimensions) -> usize {
+    (MAX_LAYER_PIXELS / dimensions.columns.max(dimensions.rows).max(1)).clamp(1, MAX_CELL_PIXELS)
+}
+
+fn is_on_path(node_status: &NodeStatus) -> bool {
+    matches!(node_status, NodeStatus::Path | NodeStatus::Start | NodeStatus::End | NodeStatus::Agent)
+}
+
+/// Everything the layer draws, compared frame to frame to skip redrawing an unchanged board.
+#[derive(PartialEq)]
+struct Markers {
+    /// The arrow of every open one-way cell; walls keep no arrow even if one was left on them.
+    arrows: Vec<Option<Direction>>,
+    /// Both ends of each portal pair.
+    portal_pairs: Vec<(usize, usize)>,
+    /// The pairs a painted path runs through at both ends, taken to be jumped.
+    jumps: Vec<(usize, usize)>,
+}
+
+impl Markers {
+    fn build(nodes: &[GridNode]) -> Self {
+        let portal_pairs: Vec<(usize, usize)> = nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(node_id, grid_node)| grid_node.portal.map(|portal| (node_id, portal.partner)))
+            .filter(|&(node_id, partner)| node_id < partner)
+            .collect();
+        // start and end alone are always painted, so a path has to show between them too
+        let jumps = portal_pairs
+            .iter()
+            .copied()
+            .filter(|&(first, second)| {
+                let ends = [&nodes[first].node_status, &nodes[second].node_status];
+                ends.iter().all(|node_status| is_on_path(node_status))
+                    && ends.iter().any(|node_status| matches!(node_status, NodeStatus::Path | NodeStatus::Agent))
+            })
+            .collect();
+
+        Markers {
+            arrows: nodes
+                .iter()
+                .map(|grid_node| grid_node.direction.filter(|_| grid_node.node_status != NodeStatus::Off))
+                .collect(),
+            portal_pairs,
+            jumps,
+        }
+    }
+}
+
+/// Redraws the markers on frames where the model's differ from the ones on screen.
+struct MarkerPainter {
+    nodes: Nodes,
+    dimensions: GridDimensions,
+    canvas_ref: NodeRef,
+    drawn_markers: Option<Markers>,
+}
+
+impl MarkerPainter {
+    fn get_center(&self, node_id: usize, cell_pixels: f64) -> (f64, f64) {
+        (
+            (self.dimensions.get_column(node_id) as f64 + 0.5) * cell_pixels,
+            (self.dimensions.get_row(node_id) as f64 + 0.5) * cell_pixels,
+        )
+    }
+
+    fn paint(&mut self) {
+        let markers = Markers::build(&self.nodes.borrow());
+        if self.drawn_markers.as_ref() == Some(&markers) {
+            return;
+        }
+
+        let Some((canvas, context)) = self.canvas_ref.cast::<HtmlCanvasElement>().and_then(|canvas| {
+            let context = canvas.get_context("2d").ok().flatten()?.dyn_into::<CanvasRenderingContext2d>().ok()?;
+            Some((canvas, context))
+        }) else {
+            return;
+        };
+
+        context.clear_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+        context.set_fill_style(&JsValue::from_str(ARROW_COLOR));
+        let cell_pixels = get_cell_pixels(self.dimensions) as f64;
+
+        markers.arrows.iter().enumerate().for_each(|(node_id, arrow)| {
+            let Some(direction) = arrow else {
+                return;
+            };
+
+            let (center_x, center_y) = self.get_center(node_id, cell_pixels);
+            let half = cell_pixels * 0.3;
+            let (dx, dy) = match direction {
+                Direction::Left => (-1.0, 0.0),
+                Direction::Right => (1.0, 0.0),
+                Direction::Up => (0.0, -1.0),
+                Direction::Down => (0.0, 1.0),
+            };
+
+            // a triangle with its tip on the side the cell lets you leave by
+            context.begin_path();
+            context.move_to(center_x + dx * half, center_y + dy * half);
+            context.line_to(center_x - dx * half - dy * half, center_y - dy * half + dx * half);
+            context.line_to(center_x - dx * half + dy * half, center_y - dy * half - dx * half);
+            context.close_path();
+            context.fill();
+        });
+
+        context.set_line_width((cell_pixels * 0.15).max(1.0));
+        markers.portal_pairs.iter().enumerate().for_each(|(pair_index, &(first, second))| {
+            context.set_stroke_style(&JsValue::from_str(PORTAL_COLORS[pair_index % PORTAL_COLORS.len()]));
+            [first, second].into_iter().for_each(|node_id| {
+                let (center_x, center_y) = self.get_center(node_id, cell_pixels);
+                context.begin_path();
+                let _ = context.arc(center_x, center_y, cell_pixels * 0.35, 0.0, std::f64::consts::TAU);
+                context.stroke();
+            });
+        });
+
+        context.set_stroke_style(&JsValue::from_str(JUMP_COLOR));
+        markers.jumps.iter().for_each(|&(first, second)| {
+            let (first_x, first_y) = self.get_center(first, cell_pixels);
+            let (second_x, second_y) = self.get_center(second, cell_pixels);
+            context.begin_path();
+            context.move_to(first_x, first_y);
+            context.line_to(second_x, second_y);
+            context.stroke();
+        });
+
+        self.drawn_markers = Some(markers);
+    }
+}
+
+#[derive(Properties)]
+pub struct MarkerLayerProps {
+    pub nodes: Nodes,
+    pub dimensions: GridDimensions,
+}
+
+impl PartialEq for MarkerLayerProps {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.nodes, &other.nodes) && self.dimensions == other.dimensions
+    }
+}
+
+/// Lies over either renderer and draws what a cell's color cannot show: one-way arrows, portal rings
+/// and the jumps a found path takes between portals.
+/// It lets every pointer event through to the board below.
+#[function_component]
+pub fn MarkerLayer(props: &MarkerLayerProps) -> Html {
+    let canvas_ref = use_node_ref();
+
+    {
+        let nodes = Rc::clone(&props.nodes);
+        let canvas_ref = canvas_ref.clone();
+
+        use_effect_with_deps(
+            move |(_, dimensions)| {
+                let mut painter = MarkerPainter { nodes, dimensions: *dimensions, canvas_ref, drawn_markers: None };
+                let registration = canvas_grid::add_overlay(move || painter.paint());
+
+                move || drop(registration)
+            },
+            (Rc::as_ptr(&props.nodes) as usize, props.dimensions),
+        );
+    }
+
+    let GridDimensions { columns, rows } = props.dimensions;
+    let cell_pixels = get_cell_pixels(props.dimensions);
+    let size_class = if columns >= rows { "w-full" } else { "h-full" };
+
+    html! {
+        <canvas
+            ref={canvas_ref}
+            class={classes!("absolute", "top-0", "left-0", "pointer-events-none", size_class)}
+            style={format!("aspect-ratio: {} / {};", columns, rows)}
+            width={(columns * cell_pixels).to_string()}
+            height={(rows * cell_pixels).to_string()}
+            />
+    }
+}
@@ -0,0 +1,91 @@
+use std::rc::Rc;
+
+use web_sys::HtmlElement;
+use yew::prelude::*;
+
+use super::canvas_grid;
+use super::viewport::ViewportFrame;
+use super::{GridDimensions, Nodes};
+
+/// The longer side of the overview is drawn with about this many pixels.
+const MINIMAP_PIXELS: usize = 200;
+
+fn get_cell_pixels(dimensions: GridDimensions) -> usize {
+    (MINIMAP_PIXELS / dimensions.columns.max(dimensions.rows).max(1)).max(1)
+}
+
+#[derive(Properties)]
+pub struct MinimapProps {
+    pub nodes: Nodes,
+    pub dimensions: GridDimensions,
+    pub frame: ViewportFrame,
+    /// Emits the clicked point as fractions of the board's width and height.
+    pub on_jump: Callback<(f64, f64)>,
+}
+
+impl PartialEq for MinimapProps {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.nodes, &other.nodes)
+            && self.dimensions == other.dimensions
+            && self.frame == other.frame
+            && self.on_jump == other.on_jump
+    }
+}
+
+/// The whole board at a glance with the zoomed-in part outlined; it is painted on the grid's frame, so it
+/// follows a running visualization.
+#[function_component]
+pub fn Minimap(props: &MinimapProps) -> Html {
+    let canvas_ref = use_node_ref();
+
+    {
+        let nodes = Rc::clone(&props.nodes);
+        let canvas_ref = canvas_ref.clone();
+
+        use_effect_with_deps(
+            move |(_, dimensions)| {
+                let registration = canvas_grid::add_canvas(nodes, *dimensions, get_cell_pixels(*dimensions), canvas_ref);
+
+                move || drop(registration)
+            },
+            (Rc::as_ptr(&props.nodes) as usize, props.dimensions),
+        );
+    }
+
+    let handle_click = {
+        let on_jump = props.on_jump.clone();
+
+        Callback::from(move |event: MouseEvent| {
+            let canvas = event.target_unchecked_into::<HtmlElement>();
+            let rect = canvas.get_bounding_client_rect();
+            if rect.width() <= 0.0 || rect.height() <= 0.0 {
+                return;
+            }
+
+            on_jump.emit((
+                (event.client_x() as f64 - rect.left()) / rect.width(),
+                (event.client_y() as f64 - rect.top()) / rect.height(),
+            ));
+        })
+    };
+
+    let GridDimensions { columns, rows } = props.dimensions;
+    let cell_pixels = get_cell_pixels(props.dimensions);
+
+    html! {
+        <div class="flex flex-col gap-2 w-56 p-3 rounded-md border-2 border-zinc-700 text-zinc-400">
+            <p>{"Overview"}</p>
+            <div class="relative w-full">
+                <canvas
+                    ref={canvas_ref}
+                    class="block w-full bg-gray-900 cursor-pointer"
+                    style={format!("aspect-ratio: {} / {}; image-rendering: pixelated;", columns, rows)}
+                    width={(columns * cell_pixels).to_string()}
+                    height={(rows * cell_pixels).to_string()}
+                    onclick={handle_click}
+                    />
+                <div class="absolute border-2 border-yellow-400 pointer-events-none" style={props.frame.get_style()} />
+            </div>
+        </div>
+    }
+}
@@ -1,44 +1,172 @@
 mod grid_actions;
 mod board_utils;
+mod grid;
+mod canvas_grid;
+mod marker_layer;
+mod viewport;
+mod minimap;
+mod inspector;
+mod queue_panel;
+mod pseudocode_panel;
+mod comparison;
+mod benchmark_panel;
+mod scenario_panel;
+mod replan_panel;
+mod fog_panel;
 
 use std::cell::RefCell;
 use std::rc::Rc;
 
 #[allow(unused_imports)]
 use gloo::console::log;
-use web_sys::HtmlElement;
+use gloo::file::callbacks::FileReader;
+use gloo::events::{EventListener, EventListenerOptions};
+use wasm_bindgen::JsCast;
+use web_sys::{File, HtmlElement, HtmlInputElement, HtmlSelectElement, KeyboardEvent};
 use yew::prelude::*;
 use yew::{Html, NodeRef};
 
-use crate::dijkstras::DijkstrasTraversal;
+use crate::dijkstras::{Algorithm, DijkstrasTraversal, QueueReplay, ShortestPathTree, TraversalEvent, ALGORITHMS};
+use crate::export;
+use crate::fog;
+use crate::heatmap::HeatmapOptions;
+use crate::image_import::{self, ImportedMap};
+use crate::lpa_star::{self, LpaStar, ReplanReport};
+use crate::movingai::MovingAiMap;
+use crate::obstacles::{self, ScatterOptions};
+use crate::recording::{self, RecordingOptions};
+use crate::terrain::{self, TerrainOptions};
 use crate::utils;
-use crate::visualizer;
-use crate::{NodeStatus, DEFAULT_COLOR, GRID_SIZE};
-use crate::utils::MouseAction;
+use crate::visualizer::{self, AnimationSpeed};
+use crate::{NodeStatus, DEFAULT_DIMENSIONS, DEFAULT_WEIGHT};
+use crate::utils::{ButtonWithModifierKey, MouseAction};
 
+use grid::Grid;
+use canvas_grid::CanvasGrid;
+pub use canvas_grid::request_frame;
+use marker_layer::MarkerLayer;
+use viewport::{BoardGeometry, Viewport, ViewportFrame, ZOOM_STEP};
+use minimap::Minimap;
 use grid_actions::{GridAction, GridState, TouchSquare};
-pub use board_utils::{GridNode, Nodes, CurrentStartNode, CurrentEndNode, BoardStatus, CLICK, CONTEXT_MENU, MOUSE_OVER};
+use inspector::{CellInfo, Inspector};
+use queue_panel::{QueuePanel, QueueSnapshot};
+use pseudocode_panel::PseudocodePanel;
+use comparison::{BoardLayout, Comparison};
+use benchmark_panel::BenchmarkPanel;
+use scenario_panel::ScenarioPanel;
+use replan_panel::ReplanPanel;
+use fog_panel::{FogPanel, FogProgress};
+pub use board_utils::{GridDimensions, GridNode, Direction, DIRECTIONS, DrawingOptions, Portal, DEFAULT_PORTAL_COST, MAX_PORTAL_COST, Nodes, CurrentStartNode, CurrentEndNode, CurrentStroke, BoardStatus, Endpoint, Brush, BrushShape, BRUSH_SHAPES, BRUSH_SIZES, Renderer, RENDERERS, Tool, TOOLS, CLICK, CONTEXT_MENU, MOUSE_DOWN, MOUSE_OVER, MOUSE_MOVE};
 
 impl GridNode {
-    pub fn build(id: usize) -> Self {
+    pub fn build(id: usize, node_status: NodeStatus, weight: usize) -> Self {
         let node_ref = NodeRef::default();
         GridNode {
             node: html! {
-                <div class={DEFAULT_COLOR} id={id.to_string()} ref={node_ref.clone()}></div>
+                <div class={utils::get_node_color(&node_status, weight)} id={id.to_string()} ref={node_ref.clone()}></div>
             },
             node_ref,
-            node_status: NodeStatus::On,
+            node_status,
+            weight,
+            direction: None,
+            portal: None,
+        }
+    }
+
+    /// Builds a node for a read-only board, so it carries no `id` to clash with the editable grid.
+    pub fn build_preview(node_status: NodeStatus, weight: usize) -> Self {
+        let node_ref = NodeRef::default();
+        GridNode {
+            node: html! {
+                <div class={utils::get_node_color(&node_status, weight)} ref={node_ref.clone()}></div>
+            },
+            node_ref,
+            node_status,
+            weight,
+            direction: None,
+            portal: None,
         }
     }
 }
 
+/// Boards with more cells than this switch to the canvas when loaded, one element per cell gets sluggish.
+const MAX_DOM_RENDERER_CELLS: usize = 100 * 100;
+
+/// What a search leaves behind besides the painted cells: the tree to trace paths in, the queue
+/// and pseudocode views, and the replan and fog scores.
+#[derive(Clone)]
+struct SearchState {
+    last_run: Rc<RefCell<Option<ShortestPathTree>>>,
+    traced_path: Rc<RefCell<Vec<usize>>>,
+    is_tracing: UseStateHandle<bool>,
+    queue_snapshot: UseStateHandle<QueueSnapshot>,
+    pseudocode_line: UseStateHandle<Option<usize>>,
+    replan_report: UseStateHandle<Option<ReplanReport>>,
+    fog_progress: UseStateHandle<Option<FogProgress>>,
+}
+
+/// Which screen the board is showing; the editor stays mounted underneath the others.
+#[derive(Clone, PartialEq)]
+enum BoardView {
+    Editor,
+    Comparison(BoardLayout),
+    Benchmark,
+}
+
 #[function_component]
 pub fn Board() -> Html {
-    let nodes = use_state(|| create_nodes());
+    let nodes = use_state(|| create_nodes(DEFAULT_DIMENSIONS));
+    let dimensions = use_state_eq(|| DEFAULT_DIMENSIONS);
+    let renderer = use_state_eq(|| Renderer::Dom);
     let current_start_node_id = use_mut_ref(|| Option::<usize>::None);
     let current_end_node_id = use_mut_ref(|| Option::<usize>::None);
     let is_first_render = use_mut_ref(|| true);
     let board_status = use_mut_ref(|| BoardStatus::NotVisualized);
+    let heatmap_options = use_mut_ref(HeatmapOptions::default);
+    let last_run = use_mut_ref(|| Option::<ShortestPathTree>::None);
+    let traced_path = use_mut_ref(Vec::<usize>::new);
+    let hovered_node_id = use_state_eq(|| Option::<usize>::None);
+    let is_tracing = use_state_eq(|| false);
+    let animation_speed = use_mut_ref(|| AnimationSpeed::Fast);
+    let is_walking_agent = use_mut_ref(|| false);
+    let tool = use_mut_ref(|| Tool::Freehand);
+    let brush = use_mut_ref(Brush::default);
+    let portal_cost = use_mut_ref(|| DEFAULT_PORTAL_COST);
+    let current_stroke: CurrentStroke = use_mut_ref(|| None);
+    let dragged_endpoint = use_mut_ref(|| Option::<Endpoint>::None);
+    let is_instant_mode = use_mut_ref(|| false);
+    let is_incremental = use_mut_ref(|| false);
+    let replanner = use_mut_ref(|| Option::<LpaStar>::None);
+    let replan_report = use_state_eq(|| Option::<ReplanReport>::None);
+    let fog_progress = use_state_eq(|| Option::<FogProgress>::None);
+    let queue_snapshot = use_state_eq(QueueSnapshot::default);
+    let pseudocode_line = use_state_eq(|| Option::<usize>::None);
+    let algorithm = use_state_eq(|| Algorithm::Dijkstra);
+    let board_view = use_state(|| BoardView::Editor);
+    let scatter_options = use_state_eq(ScatterOptions::default);
+    let terrain_options = use_state_eq(TerrainOptions::default);
+    let use_image_markers = use_mut_ref(|| true);
+    let image_reader = use_mut_ref(|| Option::<FileReader>::None);
+    let import_error = use_state_eq(|| Option::<String>::None);
+    let scatter_error = use_state_eq(|| Option::<String>::None);
+    let export_error = use_state_eq(|| Option::<String>::None);
+    let export_cell_size = use_mut_ref(|| export::DEFAULT_CELL_SIZE);
+    let recording_fps = use_mut_ref(|| recording::DEFAULT_FRAMES_PER_SECOND);
+    let viewport_ref = use_node_ref();
+    let board_ref = use_node_ref();
+    let viewport = use_mut_ref(Viewport::default);
+    let viewport_frame = use_state_eq(ViewportFrame::default);
+    let is_space_held = use_mut_ref(|| false);
+    let pan_anchor = use_mut_ref(|| Option::<(i32, i32)>::None);
+    let search_state = SearchState {
+        last_run: Rc::clone(&last_run),
+        traced_path: Rc::clone(&traced_path),
+        is_tracing: is_tracing.clone(),
+        queue_snapshot: queue_snapshot.clone(),
+        pseudocode_line: pseudocode_line.clone(),
+        replan_report: replan_report.clone(),
+        fog_progress: fog_progress.clone(),
+    };
 
     {
         let nodes = Rc::clone(&nodes);
@@ -53,49 +181,191 @@ pub fn Board() -> Html {
 
             *is_first_render.borrow_mut() = false;
 
+            let last_node_id = nodes.borrow().len() - 1;
             utils::set_start_node(nodes.borrow_mut(), 0, current_start_node_id);
             utils::set_end_node(
                 nodes.borrow_mut(),
-                GRID_SIZE - 1,
+                last_node_id,
                 current_end_node_id,
             );
         });
     }
 
-    let handle_drag_start = {
-        Callback::from(|event: DragEvent| {
-            event.prevent_default();
-        })
-    };
+    {
+        let is_space_held = Rc::clone(&is_space_held);
+
+        // space pans while held, wherever the focus is
+        use_effect_with_deps(
+            move |_| {
+                let window = gloo::utils::window();
+                let key_down = {
+                    let is_space_held = Rc::clone(&is_space_held);
+                    EventListener::new_with_options(&window, "keydown", EventListenerOptions::enable_prevent_default(), move |event| {
+                        let Some(event) = event.dyn_ref::<KeyboardEvent>() else {
+                            return;
+                        };
+                        let is_typing = event.target().is_some_and(|target| target.has_type::<HtmlInputElement>());
+                        if event.code() == "Space" && !is_typing {
+                            // keeps the page from scrolling and focused buttons from firing
+                            event.prevent_default();
+                            *is_space_held.borrow_mut() = true;
+                        }
+                    })
+                };
+                let key_up = EventListener::new(&window, "keyup", move |event| {
+                    if event.dyn_ref::<KeyboardEvent>().is_some_and(|event| event.code() == "Space") {
+                        *is_space_held.borrow_mut() = false;
+                    }
+                });
+
+                move || drop((key_down, key_up))
+            },
+            (),
+        );
+    }
 
     let handle_mouse_action = {
         let nodes = Rc::clone(&nodes);
         let current_start_node_id = Rc::clone(&current_start_node_id);
         let current_end_node_id = Rc::clone(&current_end_node_id);
+        let current_stroke = Rc::clone(&current_stroke);
+        let tool = Rc::clone(&tool);
+        let brush = Rc::clone(&brush);
+        let portal_cost = Rc::clone(&portal_cost);
+        let dragged_endpoint = Rc::clone(&dragged_endpoint);
+        let is_instant_mode = Rc::clone(&is_instant_mode);
+        let is_incremental = Rc::clone(&is_incremental);
+        let replanner = Rc::clone(&replanner);
+        let replan_report = replan_report.clone();
         let board_status = Rc::clone(&board_status);
+        let last_run = Rc::clone(&last_run);
+        let traced_path = Rc::clone(&traced_path);
+        let is_tracing = is_tracing.clone();
+        let queue_snapshot = queue_snapshot.clone();
+        let pseudocode_line = pseudocode_line.clone();
+        let set_hovered_node_id = hovered_node_id.setter();
+        let nodes_address = Rc::as_ptr(&nodes) as usize;
+        let dimensions = *dimensions;
+        let algorithm = *algorithm;
+        let viewport_ref = viewport_ref.clone();
+        let board_ref = board_ref.clone();
+        let viewport = Rc::clone(&viewport);
+        let is_space_held = Rc::clone(&is_space_held);
+        let pan_anchor = Rc::clone(&pan_anchor);
 
-        Callback::from(move |event: MouseEvent| {
+        // memoized so that hovering, which re-renders the board, does not re-render every grid cell
+        use_memo(|_| Callback::from(move |event: MouseEvent| {
             event.prevent_default();
 
-            if let BoardStatus::Visualizing | BoardStatus::Visualized = *board_status.borrow() {
+            let Some(geometry) = get_board_geometry(&viewport_ref, &board_ref, *viewport.borrow(), dimensions) else {
+                return;
+            };
+
+            // shows the new solution straight away after an edit on a solved board
+            let solve_again = || {
+                let (Some(start_node_id), Some(end_node_id)) = (*current_start_node_id.borrow(), *current_end_node_id.borrow()) else {
+                    return;
+                };
+
+                clear_traced_path(&nodes, &traced_path);
+                is_tracing.set(false);
+                queue_snapshot.set(QueueSnapshot::default());
+                pseudocode_line.set(None);
+
+                if *is_incremental.borrow() {
+                    let (report, tree) = replan_instantly(&nodes, &mut replanner.borrow_mut(), dimensions, start_node_id, end_node_id);
+                    replan_report.set(Some(report));
+                    *last_run.borrow_mut() = Some(tree);
+                } else {
+                    replan_report.set(None);
+                    *last_run.borrow_mut() = Some(solve_instantly(&nodes, dimensions, start_node_id, end_node_id, algorithm));
+                }
+            };
+
+            let hovered_node_id = GridAction::get_node_id(&event, &geometry);
+            if let MOUSE_OVER | MOUSE_MOVE = event.type_().as_str() {
+                set_hovered_node_id.set(hovered_node_id);
+            }
+
+            if *is_space_held.borrow() || pan_anchor.borrow().is_some() {
+                return;
+            }
+
+            if let BoardStatus::Visualizing = *board_status.borrow() {
+                return;
+            }
+
+            // start and end can be dragged even on a solved board, which is then solved again on the spot
+            let current_drag = *dragged_endpoint.borrow();
+            match (event.type_().as_str(), current_drag) {
+                (MOUSE_DOWN, _) => {
+                    let is_plain_left_button = event.button() == 0 && !event.ctrl_key() && !event.shift_key();
+                    *dragged_endpoint.borrow_mut() = hovered_node_id
+                        .filter(|_| is_plain_left_button)
+                        .and_then(|node_id| Endpoint::from_status(&nodes.borrow()[node_id].node_status));
+                    if dragged_endpoint.borrow().is_some() {
+                        return;
+                    }
+                }
+                (MOUSE_OVER | MOUSE_MOVE, Some(endpoint)) if event.buttons() == 1 => {
+                    let Some(node_id) = hovered_node_id else {
+                        return;
+                    };
+                    let is_moved = drag_endpoint(&nodes, endpoint, node_id, Rc::clone(&current_start_node_id), Rc::clone(&current_end_node_id));
+                    if is_moved && matches!(*board_status.borrow(), BoardStatus::Visualized) {
+                        solve_again();
+                    }
+                    return;
+                }
+                (CLICK, Some(_)) => {
+                    *dragged_endpoint.borrow_mut() = None;
+                    return;
+                }
+                // the button came up somewhere off the board
+                (_, Some(_)) => *dragged_endpoint.borrow_mut() = None,
+                _ => {}
+            }
+
+            let is_solved = matches!(*board_status.borrow(), BoardStatus::Visualized);
+            if is_solved && !*is_instant_mode.borrow() {
                 return;
             }
 
             let mouse_action = match event.type_().as_str() {
+                MOUSE_DOWN => MouseAction::Press,
                 CLICK | CONTEXT_MENU => MouseAction::Click,
-                MOUSE_OVER => MouseAction::Move,
+                MOUSE_OVER | MOUSE_MOVE => MouseAction::Move,
                 _ => return,
             };
 
-            
             let grid_state = GridState::new(
                 Rc::clone(&nodes),
                 Rc::clone(&current_start_node_id),
                 Rc::clone(&current_end_node_id),
+                Rc::clone(&current_stroke),
+                DrawingOptions { tool: *tool.borrow(), brush: *brush.borrow(), portal_cost: *portal_cost.borrow() },
+                geometry,
             );
             let grid_action = GridAction::new(&event, mouse_action, grid_state);
 
             if let Some(grid_action) = grid_action {
+                if is_solved {
+                    if let ButtonWithModifierKey::Other() = grid_action.button_with_modifier {
+                        grid_action.trigger_node();
+                        return;
+                    }
+
+                    if let (true, Some(start_node_id), Some(end_node_id)) = (*is_incremental.borrow(), *current_start_node_id.borrow(), *current_end_node_id.borrow()) {
+                        sync_replanner(&mut replanner.borrow_mut(), &nodes.borrow(), dimensions, start_node_id, end_node_id);
+                    }
+                    // dragging over cells the stroke already painted leaves the solution as it is
+                    if grid_action.trigger_node() {
+                        clear_traversed_nodes(&nodes);
+                        solve_again();
+                    }
+                    return;
+                }
+
                 grid_action.trigger_node();
 
                 let mut board_status_borrow = board_status.borrow_mut();
@@ -104,14 +374,51 @@ pub fn Board() -> Html {
                 }
             }
 
-        })
+        // a loaded map replaces the nodes, so the callback has to be rebuilt around the new ones
+        }), (nodes_address, dimensions, algorithm))
     };
 
-    fn create_nodes() -> Nodes {
-        let grid_as_vec = Rc::new(RefCell::new(Vec::with_capacity(GRID_SIZE)));
+    /// Measures where the board is right now; `None` until it is mounted.
+    fn get_board_geometry(
+        viewport_ref: &NodeRef,
+        board_ref: &NodeRef,
+        viewport: Viewport,
+        dimensions: GridDimensions,
+    ) -> Option<BoardGeometry> {
+        let viewport_element = viewport_ref.cast::<HtmlElement>()?;
+        let board_element = board_ref
+            .cast::<HtmlElement>()?
+            .first_element_child()?
+            .dyn_into::<HtmlElement>()
+            .ok()?;
+        let viewport_rect = viewport_element.get_bounding_client_rect();
+
+        Some(BoardGeometry {
+            origin_x: viewport_rect.left() + viewport_element.client_left() as f64,
+            origin_y: viewport_rect.top() + viewport_element.client_top() as f64,
+            viewport,
+            board_width: board_element.offset_width() as f64,
+            board_height: board_element.offset_height() as f64,
+            view_width: viewport_element.client_width() as f64,
+            view_height: viewport_element.client_height() as f64,
+            dimensions,
+        })
+    }
+
+    /// Moves the board to `geometry`'s viewport and outlines what is now in view on the overview.
+    fn apply_viewport(board_ref: &NodeRef, geometry: &BoardGeometry, viewport_frame: &UseStateHandle<ViewportFrame>) {
+        if let Some(board_element) = board_ref.cast::<HtmlElement>() {
+            let _ = board_element.set_attribute("style", &geometry.viewport.get_transform_style());
+        }
+        viewport_frame.set(geometry.get_visible_frame());
+    }
+
+    fn create_nodes(dimensions: GridDimensions) -> Nodes {
+        let num_nodes = dimensions.get_num_nodes();
+        let grid_as_vec = Rc::new(RefCell::new(Vec::with_capacity(num_nodes)));
 
-        (0..GRID_SIZE).for_each(|i| {
-            let grid_node = GridNode::build(i);
+        (0..num_nodes).for_each(|i| {
+            let grid_node = GridNode::build(i, NodeStatus::On, DEFAULT_WEIGHT);
             grid_as_vec.borrow_mut().push(grid_node);
         });
 
@@ -120,23 +427,7 @@ pub fn Board() -> Html {
 
     fn clear_traversed_nodes(nodes: &Nodes) {
         let mut nodes_borrow = nodes.borrow_mut();
-        (0..GRID_SIZE).for_each(|i| {
-            if let Some(node_ref) = nodes_borrow
-                .get(i)
-                .unwrap()
-                .node_ref
-                .cast::<HtmlElement>()
-            {
-                let node_status = &mut nodes_borrow[i].node_status;
-                if *node_status == NodeStatus::Visited
-                    || *node_status == NodeStatus::Path
-                {
-                    utils::set_square_color(&node_ref, NodeStatus::On);
-                    utils::set_node_status(node_status, NodeStatus::On);
-                }
-            }
-        })
-
+        nodes_borrow.iter_mut().for_each(utils::clear_search_marks)
     }
 
     fn reset_board(
@@ -145,34 +436,178 @@ pub fn Board() -> Html {
         current_end_node_id: CurrentEndNode,
     ) {
         let mut nodes_borrow = nodes.borrow_mut();
-        (0..GRID_SIZE).for_each(|i| {
-            nodes_borrow[i].node_status = NodeStatus::On;
-            if let Some(node_ref) = nodes_borrow
-                .get(i)
-                .unwrap()
-                .node_ref
-                .cast::<HtmlElement>()
-            {
-                utils::set_node_on(node_ref, &mut nodes_borrow[i].node_status);
+        nodes_borrow.iter_mut().for_each(|grid_node| {
+            grid_node.weight = DEFAULT_WEIGHT;
+            grid_node.direction = None;
+            grid_node.portal = None;
+            if let Some(node_ref) = grid_node.node_ref.cast::<HtmlElement>() {
+                utils::clear_square_overlay(&node_ref);
             }
+            utils::set_node_on(grid_node);
         });
 
         *current_start_node_id.borrow_mut() = None;
         *current_end_node_id.borrow_mut() = None;
+        let last_node_id = nodes_borrow.len() - 1;
         utils::set_start_node(nodes_borrow, 0, current_start_node_id);
         utils::set_end_node(
             nodes.borrow_mut(),
-            GRID_SIZE - 1,
+            last_node_id,
             current_end_node_id,
         );
     }
 
 
+    /// Rebuilds the walls, and the weights when given, from a generator; start and end keep their place
+    /// and one-way arrows and portals are cleared.
+    fn apply_generated_cells(nodes: &Nodes, walls: &[bool], weights: Option<&[usize]>) {
+        let mut nodes_borrow = nodes.borrow_mut();
+        (0..nodes_borrow.len()).for_each(|i| {
+            nodes_borrow[i].direction = None;
+            nodes_borrow[i].portal = None;
+            if let Some(weights) = weights {
+                nodes_borrow[i].weight = weights[i];
+            }
+
+            if let NodeStatus::Start | NodeStatus::End = nodes_borrow[i].node_status {
+                return;
+            }
+
+            let new_status = if walls[i] { NodeStatus::Off } else { NodeStatus::On };
+
+            if let Some(node_ref) = nodes_borrow
+                .get(i)
+                .unwrap()
+                .node_ref
+                .cast::<HtmlElement>()
+            {
+                utils::clear_square_overlay(&node_ref);
+                utils::set_node_color(&node_ref, new_status.clone(), nodes_borrow[i].weight);
+            }
+            utils::set_node_status(&mut nodes_borrow[i].node_status, new_status);
+        });
+    }
+
+    /// Places start and end afresh, so they may swap or land on each other's old cell.
+    fn move_endpoints(
+        nodes: &Nodes,
+        new_start_node_id: usize,
+        new_end_node_id: usize,
+        current_start_node_id: CurrentStartNode,
+        current_end_node_id: CurrentEndNode,
+    ) {
+        {
+            let mut nodes_borrow = nodes.borrow_mut();
+            let current_ids = [current_start_node_id.borrow_mut().take(), current_end_node_id.borrow_mut().take()];
+            current_ids.into_iter().flatten().for_each(|id| utils::set_node_on(&mut nodes_borrow[id]));
+        }
+
+        utils::set_start_node(nodes.borrow_mut(), new_start_node_id, current_start_node_id);
+        utils::set_end_node(nodes.borrow_mut(), new_end_node_id, current_end_node_id);
+    }
+
+    /// Moves a dragged start or end onto another cell; walls and the other endpoint stop it.
+    fn drag_endpoint(
+        nodes: &Nodes,
+        endpoint: Endpoint,
+        node_id: usize,
+        current_start_node_id: CurrentStartNode,
+        current_end_node_id: CurrentEndNode,
+    ) -> bool {
+        if let NodeStatus::Off | NodeStatus::Start | NodeStatus::End = nodes.borrow()[node_id].node_status {
+            return false;
+        }
+
+        match endpoint {
+            Endpoint::Start => utils::set_start_node(nodes.borrow_mut(), node_id, current_start_node_id),
+            Endpoint::End => utils::set_end_node(nodes.borrow_mut(), node_id, current_end_node_id),
+        }
+        true
+    }
+
+    /// Runs the search again and paints its result without animating it.
+    fn solve_instantly(
+        nodes: &Nodes,
+        dimensions: GridDimensions,
+        start_node_id: usize,
+        end_node_id: usize,
+        algorithm: Algorithm,
+    ) -> ShortestPathTree {
+        clear_traversed_nodes(nodes);
+        let dijkstras_traversal = DijkstrasTraversal::run_with_algorithm(&nodes.borrow(), dimensions, start_node_id, end_node_id, algorithm);
+        visualizer::paint_instantly(nodes, &dijkstras_traversal.get_steps(), &dijkstras_traversal.get_path());
+
+        dijkstras_traversal.get_shortest_path_tree()
+    }
+
+    /// Catches the planner up with the board before an edit, so the replan after it measures only that edit.
+    fn sync_replanner(
+        replanner: &mut Option<LpaStar>,
+        nodes: &[GridNode],
+        dimensions: GridDimensions,
+        start_node_id: usize,
+        end_node_id: usize,
+    ) {
+        match replanner {
+            Some(lpa_star) if lpa_star.is_planning(dimensions, start_node_id, end_node_id) => {
+                lpa_star.update_costs(nodes);
+                lpa_star.compute_shortest_path();
+            }
+            _ => {
+                let mut lpa_star = LpaStar::new(nodes, dimensions, start_node_id, end_node_id);
+                lpa_star.compute_shortest_path();
+                *replanner = Some(lpa_star);
+            }
+        }
+    }
+
+    /// Repairs the previous search after an edit and paints just the cells it had to expand again.
+    /// A moved endpoint cannot reuse anything, so the planner then starts over.
+    fn replan_instantly(
+        nodes: &Nodes,
+        replanner: &mut Option<LpaStar>,
+        dimensions: GridDimensions,
+        start_node_id: usize,
+        end_node_id: usize,
+    ) -> (ReplanReport, ShortestPathTree) {
+        clear_traversed_nodes(nodes);
+        let lpa_star = match replanner {
+            Some(lpa_star) if lpa_star.is_planning(dimensions, start_node_id, end_node_id) => lpa_star,
+            _ => replanner.insert(LpaStar::new(&nodes.borrow(), dimensions, start_node_id, end_node_id)),
+        };
+
+        let report = lpa_star::replan(lpa_star, &nodes.borrow());
+        visualizer::paint_expanded_instantly(nodes, &report.expanded, &lpa_star.get_path());
+
+        (report, lpa_star.get_shortest_path_tree())
+    }
+
+    fn clear_traced_path(nodes: &Nodes, traced_path: &RefCell<Vec<usize>>) {
+        let nodes_borrow = nodes.borrow();
+        traced_path.borrow_mut().drain(..).for_each(|node_id| {
+            if let Some(node_ref) = nodes_borrow[node_id].node_ref.cast::<HtmlElement>() {
+                utils::remove_trace_highlight(&node_ref);
+            }
+        });
+    }
+
+    /// Drops everything the last search left behind, for when the board it ran on is gone.
+    fn reset_search_state(nodes: &Nodes, search_state: &SearchState) {
+        clear_traced_path(nodes, &search_state.traced_path);
+        search_state.is_tracing.set(false);
+        *search_state.last_run.borrow_mut() = None;
+        search_state.queue_snapshot.set(QueueSnapshot::default());
+        search_state.pseudocode_line.set(None);
+        search_state.replan_report.set(None);
+        search_state.fog_progress.set(None);
+    }
+
     let handle_reset_board = {
         let nodes = Rc::clone(&nodes);
         let current_start_node_id = Rc::clone(&current_start_node_id);
         let current_end_node_id = Rc::clone(&current_end_node_id);
         let board_status = Rc::clone(&board_status);
+        let search_state = search_state.clone();
 
         Callback::from(move |_| {
             let mut board_status_borrow = board_status.borrow_mut();
@@ -180,6 +615,8 @@ pub fn Board() -> Html {
                 return;
             }
 
+            reset_search_state(&nodes, &search_state);
+
             let current_start_node_id = Rc::clone(&current_start_node_id);
             let current_end_node_id = Rc::clone(&current_end_node_id);
             reset_board(
@@ -196,6 +633,7 @@ pub fn Board() -> Html {
     let handle_clear_traversed_nodes = {
         let nodes = Rc::clone(&nodes);
         let board_status = Rc::clone(&board_status);
+        let search_state = search_state.clone();
 
         Callback::from(move |_| {
             let mut board_status_borrow = board_status.borrow_mut();
@@ -203,6 +641,7 @@ pub fn Board() -> Html {
                 return;
             }
 
+            reset_search_state(&nodes, &search_state);
             clear_traversed_nodes(&nodes);
 
             *board_status_borrow = BoardStatus::NotVisualized;
@@ -214,6 +653,13 @@ pub fn Board() -> Html {
         let current_end_node_id = Rc::clone(&current_end_node_id);
         let nodes = Rc::clone(&nodes);
         let board_status = Rc::clone(&board_status);
+        let last_run = Rc::clone(&last_run);
+        let animation_speed = Rc::clone(&animation_speed);
+        let is_walking_agent = Rc::clone(&is_walking_agent);
+        let queue_snapshot = queue_snapshot.clone();
+        let pseudocode_line = pseudocode_line.clone();
+        let algorithm = *algorithm;
+        let dimensions = *dimensions;
 
         Callback::from(move |_| {
             if let BoardStatus::Visualizing | BoardStatus::Visualized = *board_status.borrow() {
@@ -222,11 +668,24 @@ pub fn Board() -> Html {
 
             *board_status.borrow_mut() = BoardStatus::Visualizing;
 
-            let dijkstras_traversal = DijkstrasTraversal::run(
+            let dijkstras_traversal = DijkstrasTraversal::run_with_algorithm(
                 &nodes.borrow(),
+                dimensions,
                 current_start_node_id.borrow().unwrap(),
                 current_end_node_id.borrow().unwrap(),
+                algorithm,
             );
+            *last_run.borrow_mut() = Some(dijkstras_traversal.get_shortest_path_tree());
+
+            let queue_replay = RefCell::new(QueueReplay::default());
+            let queue_snapshot = queue_snapshot.clone();
+            let pseudocode_line = pseudocode_line.clone();
+            let progress_callback = move |applied_events: &[TraversalEvent], next_node_id: Option<usize>| {
+                let mut queue_replay = queue_replay.borrow_mut();
+                applied_events.iter().for_each(|event| queue_replay.apply_event(event));
+                queue_snapshot.set(QueueSnapshot::build(&queue_replay, next_node_id));
+                pseudocode_line.set(applied_events.last().map(|event| event.get_pseudocode_line()));
+            };
 
             let board_status = Rc::clone(&board_status);
             let end_of_visualization_callback = move || {
@@ -234,30 +693,1006 @@ pub fn Board() -> Html {
             };
             visualizer::visualize(
                 Rc::clone(&nodes),
-                dijkstras_traversal.get_traversed_nodes(),
+                dijkstras_traversal.get_steps(),
                 dijkstras_traversal.get_path(),
+                *animation_speed.borrow(),
+                *is_walking_agent.borrow(),
+                progress_callback,
+                end_of_visualization_callback,
+            );
+        })
+    };
+
+    let handle_measure_full_replan = {
+        let nodes = Rc::clone(&nodes);
+        let replanner = Rc::clone(&replanner);
+        let replan_report = replan_report.clone();
+
+        Callback::from(move |_| {
+            let replanner = replanner.borrow();
+            let (Some(mut report), Some(lpa_star)) = ((*replan_report).clone(), replanner.as_ref()) else {
+                return;
+            };
+
+            report.measure_full_replan(lpa_star, &nodes.borrow());
+            replan_report.set(Some(report));
+        })
+    };
+
+    let handle_explore_fog = {
+        let current_start_node_id = Rc::clone(&current_start_node_id);
+        let current_end_node_id = Rc::clone(&current_end_node_id);
+        let nodes = Rc::clone(&nodes);
+        let board_status = Rc::clone(&board_status);
+        let last_run = Rc::clone(&last_run);
+        let animation_speed = Rc::clone(&animation_speed);
+        let fog_progress = fog_progress.clone();
+        let dimensions = *dimensions;
+
+        Callback::from(move |sensor_radius: usize| {
+            if let BoardStatus::Visualizing | BoardStatus::Visualized = *board_status.borrow() {
+                return;
+            }
+
+            *board_status.borrow_mut() = BoardStatus::Visualizing;
+
+            let fog_run = Rc::new(fog::explore(
+                &nodes.borrow(),
+                dimensions,
+                current_start_node_id.borrow().unwrap(),
+                current_end_node_id.borrow().unwrap(),
+                sensor_radius,
+            ));
+            // the walk is no search, so there is no tree to trace
+            *last_run.borrow_mut() = None;
+
+            let progress_callback = {
+                let fog_run = Rc::clone(&fog_run);
+                let fog_progress = fog_progress.clone();
+                move |step_index: usize| fog_progress.set(Some(FogProgress::build(&fog_run, step_index)))
+            };
+
+            let board_status = Rc::clone(&board_status);
+            let end_of_visualization_callback = move || {
+                *board_status.borrow_mut() = BoardStatus::Visualized;
+            };
+            visualizer::visualize_fog(
+                Rc::clone(&nodes),
+                fog_run.steps.clone(),
+                *animation_speed.borrow(),
+                progress_callback,
+                end_of_visualization_callback,
+            );
+        })
+    };
+
+    let handle_scatter_walls = {
+        let nodes = Rc::clone(&nodes);
+        let current_start_node_id = Rc::clone(&current_start_node_id);
+        let current_end_node_id = Rc::clone(&current_end_node_id);
+        let board_status = Rc::clone(&board_status);
+        let search_state = search_state.clone();
+        let scatter_options = scatter_options.clone();
+        let scatter_error = scatter_error.clone();
+        let dimensions = *dimensions;
+
+        Callback::from(move |_| {
+            let mut board_status_borrow = board_status.borrow_mut();
+            if let BoardStatus::Visualizing = *board_status_borrow {
+                return;
+            }
+
+            let Some(scattered_board) = obstacles::scatter_walls_with_options(
+                dimensions,
+                *scatter_options,
+                current_start_node_id.borrow().unwrap(),
+                current_end_node_id.borrow().unwrap(),
+            ) else {
+                scatter_error.set(Some("no open path found at this wall density, try a lower one".to_string()));
+                return;
+            };
+
+            reset_search_state(&nodes, &search_state);
+            scatter_error.set(None);
+            let walls: Vec<bool> = scattered_board
+                .node_statuses
+                .iter()
+                .map(|node_status| *node_status == NodeStatus::Off)
+                .collect();
+            apply_generated_cells(&nodes, &walls, None);
+            // show the seed that was actually used so the board can be reproduced
+            scatter_options.set(ScatterOptions { seed: scattered_board.seed, ..*scatter_options });
+
+            *board_status_borrow = BoardStatus::NotVisualized;
+        })
+    };
+
+    let handle_change_scatter_density = {
+        let scatter_options = scatter_options.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(density_percent) = input.value().parse::<f64>() {
+                let wall_density = (density_percent / 100.0).clamp(0.0, 1.0);
+                scatter_options.set(ScatterOptions { wall_density, ..*scatter_options });
+            }
+        })
+    };
+
+    let handle_change_scatter_seed = {
+        let scatter_options = scatter_options.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(seed) = input.value().parse::<u64>() {
+                scatter_options.set(ScatterOptions { seed, ..*scatter_options });
+            }
+        })
+    };
+
+    let handle_toggle_ensure_path = {
+        let scatter_options = scatter_options.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            scatter_options.set(ScatterOptions { ensure_path: input.checked(), ..*scatter_options });
+        })
+    };
+
+    let handle_generate_terrain = {
+        let nodes = Rc::clone(&nodes);
+        let current_start_node_id = Rc::clone(&current_start_node_id);
+        let current_end_node_id = Rc::clone(&current_end_node_id);
+        let board_status = Rc::clone(&board_status);
+        let search_state = search_state.clone();
+        let terrain_options = terrain_options.clone();
+        let dimensions = *dimensions;
+
+        Callback::from(move |_| {
+            let mut board_status_borrow = board_status.borrow_mut();
+            if let BoardStatus::Visualizing = *board_status_borrow {
+                return;
+            }
+
+            let generated_terrain = terrain::generate_terrain(
+                dimensions,
+                *terrain_options,
+                current_start_node_id.borrow().unwrap(),
+                current_end_node_id.borrow().unwrap(),
+            );
+
+            reset_search_state(&nodes, &search_state);
+            apply_generated_cells(&nodes, &generated_terrain.walls, Some(&generated_terrain.weights));
+
+            *board_status_borrow = BoardStatus::NotVisualized;
+        })
+    };
+
+    let handle_change_terrain_scale = {
+        let terrain_options = terrain_options.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(scale) = input.value().parse::<f64>() {
+                terrain_options.set(TerrainOptions { scale: scale.max(1.0), ..*terrain_options });
+            }
+        })
+    };
+
+    let handle_change_terrain_octaves = {
+        let terrain_options = terrain_options.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(octaves) = input.value().parse::<u32>() {
+                terrain_options.set(TerrainOptions { octaves: octaves.clamp(1, 6), ..*terrain_options });
+            }
+        })
+    };
+
+    let handle_change_terrain_seed = {
+        let terrain_options = terrain_options.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(seed) = input.value().parse::<u64>() {
+                terrain_options.set(TerrainOptions { seed, ..*terrain_options });
+            }
+        })
+    };
+
+    let handle_change_terrain_walls = {
+        let terrain_options = terrain_options.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            // an empty field turns the walls off
+            let wall_threshold = input.value().parse::<f64>().ok().map(|percent| (percent / 100.0).clamp(0.0, 1.0));
+            terrain_options.set(TerrainOptions { wall_threshold, ..*terrain_options });
+        })
+    };
+
+    let handle_imported_image = {
+        let nodes = Rc::clone(&nodes);
+        let current_start_node_id = Rc::clone(&current_start_node_id);
+        let current_end_node_id = Rc::clone(&current_end_node_id);
+        let board_status = Rc::clone(&board_status);
+        let search_state = search_state.clone();
+        let use_image_markers = Rc::clone(&use_image_markers);
+        let import_error = import_error.clone();
+        let dimensions = *dimensions;
+
+        Callback::from(move |bytes: Vec<u8>| {
+            let mut board_status_borrow = board_status.borrow_mut();
+            if let BoardStatus::Visualizing = *board_status_borrow {
+                return;
+            }
+
+            let imported_map = match image_import::decode_png(&bytes) {
+                Ok(image) => image_import::map_image(&image, dimensions.columns, dimensions.rows, *use_image_markers.borrow()),
+                Err(error) => {
+                    import_error.set(Some(error.to_string()));
+                    return;
+                }
+            };
+            let ImportedMap { walls, weights, start_node_id, end_node_id } = imported_map;
+
+            reset_search_state(&nodes, &search_state);
+            import_error.set(None);
+
+            let new_start_node_id = start_node_id.or(*current_start_node_id.borrow()).unwrap();
+            let new_end_node_id = end_node_id.or(*current_end_node_id.borrow()).unwrap();
+            // a single marker can land on the endpoint it does not move
+            if new_start_node_id == new_end_node_id {
+                import_error.set(Some("the marker lands on the other endpoint, so start and end were left where they were".to_string()));
+            } else {
+                move_endpoints(
+                    &nodes,
+                    new_start_node_id,
+                    new_end_node_id,
+                    Rc::clone(&current_start_node_id),
+                    Rc::clone(&current_end_node_id),
+                );
+            }
+            apply_generated_cells(&nodes, &walls, Some(&weights));
+
+            *board_status_borrow = BoardStatus::NotVisualized;
+        })
+    };
+
+    let read_image_file = {
+        let image_reader = Rc::clone(&image_reader);
+
+        move |file: File| {
+            let handle_imported_image = handle_imported_image.clone();
+            let file = gloo::file::File::from(file);
+            // the read is cancelled if its reader is dropped, so keep it until the next import
+            *image_reader.borrow_mut() = Some(gloo::file::callbacks::read_as_bytes(&file, move |result| {
+                if let Ok(bytes) = result {
+                    handle_imported_image.emit(bytes);
+                }
+            }));
+        }
+    };
+
+    let handle_choose_image = {
+        let read_image_file = read_image_file.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                read_image_file(file);
+            }
+            // clearing the input lets the same file be imported again after editing the board
+            input.set_value("");
+        })
+    };
+
+    let handle_drop_image = Callback::from(move |event: DragEvent| {
+        event.prevent_default();
+        if let Some(file) = event.data_transfer().and_then(|data| data.files()).and_then(|files| files.get(0)) {
+            read_image_file(file);
+        }
+    });
+
+    let handle_toggle_image_markers = {
+        let use_image_markers = Rc::clone(&use_image_markers);
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            *use_image_markers.borrow_mut() = input.checked();
+        })
+    };
+
+    let handle_change_export_cell_size = {
+        let export_cell_size = Rc::clone(&export_cell_size);
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(cell_size) = input.value().parse::<usize>() {
+                *export_cell_size.borrow_mut() = cell_size.clamp(1, 64);
+            }
+        })
+    };
+
+    let handle_export_svg = {
+        let nodes = Rc::clone(&nodes);
+        let export_cell_size = Rc::clone(&export_cell_size);
+        let export_error = export_error.clone();
+        let columns = dimensions.columns;
+
+        Callback::from(move |_| {
+            export_error.set(None);
+            let svg = export::render_svg(&nodes.borrow(), columns, *export_cell_size.borrow());
+            utils::download_file("board.svg", "image/svg+xml", svg.as_bytes());
+        })
+    };
+
+    let handle_export_png = {
+        let nodes = Rc::clone(&nodes);
+        let export_cell_size = Rc::clone(&export_cell_size);
+        let export_error = export_error.clone();
+        let columns = dimensions.columns;
+
+        Callback::from(move |_| {
+            match export::render_png(&nodes.borrow(), columns, *export_cell_size.borrow()) {
+                Ok(png) => {
+                    export_error.set(None);
+                    utils::download_file("board.png", "image/png", &png);
+                }
+                Err(error) => export_error.set(Some(error.to_string())),
+            }
+        })
+    };
+
+    let handle_change_recording_fps = {
+        let recording_fps = Rc::clone(&recording_fps);
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(frames_per_second) = input.value().parse::<u16>() {
+                *recording_fps.borrow_mut() = frames_per_second.clamp(1, 50);
+            }
+        })
+    };
+
+    let handle_record_gif = {
+        let nodes = Rc::clone(&nodes);
+        let current_start_node_id = Rc::clone(&current_start_node_id);
+        let current_end_node_id = Rc::clone(&current_end_node_id);
+        let export_cell_size = Rc::clone(&export_cell_size);
+        let recording_fps = Rc::clone(&recording_fps);
+        let animation_speed = Rc::clone(&animation_speed);
+        let export_error = export_error.clone();
+        let algorithm = *algorithm;
+        let dimensions = *dimensions;
+
+        Callback::from(move |_| {
+            // record from the bare layout so the GIF starts before any search results
+            let layout = BoardLayout::capture(
+                &nodes.borrow(),
+                dimensions,
+                current_start_node_id.borrow().unwrap(),
+                current_end_node_id.borrow().unwrap(),
+            );
+            let layout_nodes = layout.create_nodes();
+            let mut layout_nodes = layout_nodes.borrow_mut();
+            let traversal = DijkstrasTraversal::run_with_algorithm(
+                &layout_nodes,
+                layout.dimensions,
+                layout.start_node_id,
+                layout.end_node_id,
+                algorithm,
+            );
+            let options = RecordingOptions {
+                cell_size: *export_cell_size.borrow(),
+                frames_per_second: *recording_fps.borrow(),
+                steps_per_frame: animation_speed.borrow().get_steps_per_frame(),
+            };
+
+            match recording::record_gif(
+                &mut layout_nodes,
+                layout.dimensions.columns,
+                &traversal.get_steps(),
+                &traversal.get_path(),
+                options,
+            ) {
+                Ok(gif) => {
+                    export_error.set(None);
+                    utils::download_file("run.gif", "image/gif", &gif);
+                }
+                Err(error) => export_error.set(Some(error.to_string())),
+            }
+        })
+    };
+
+    let handle_show_shortest_path_tree = {
+        let current_start_node_id = Rc::clone(&current_start_node_id);
+        let current_end_node_id = Rc::clone(&current_end_node_id);
+        let nodes = Rc::clone(&nodes);
+        let board_status = Rc::clone(&board_status);
+        let heatmap_options = Rc::clone(&heatmap_options);
+        let last_run = Rc::clone(&last_run);
+        let dimensions = *dimensions;
+
+        Callback::from(move |_| {
+            if let BoardStatus::Visualizing | BoardStatus::Visualized = *board_status.borrow() {
+                return;
+            }
+
+            *board_status.borrow_mut() = BoardStatus::Visualizing;
+
+            let start_node_id = current_start_node_id.borrow().unwrap();
+            let end_node_id = current_end_node_id.borrow().unwrap();
+            let shortest_path_tree = DijkstrasTraversal::run_to_exhaustion(&nodes.borrow(), dimensions, start_node_id);
+            let path = shortest_path_tree.path_to(start_node_id, end_node_id);
+            *last_run.borrow_mut() = Some(shortest_path_tree.clone());
+
+            let board_status = Rc::clone(&board_status);
+            let end_of_visualization_callback = move || {
+                *board_status.borrow_mut() = BoardStatus::Visualized;
+            };
+            visualizer::visualize_tree(
+                Rc::clone(&nodes),
+                dimensions,
+                shortest_path_tree,
+                path,
+                *heatmap_options.borrow(),
                 end_of_visualization_callback,
             );
         })
     };
 
+    let handle_toggle_distances = {
+        let heatmap_options = Rc::clone(&heatmap_options);
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            heatmap_options.borrow_mut().show_distances = input.checked();
+        })
+    };
+
+    let handle_toggle_arrows = {
+        let heatmap_options = Rc::clone(&heatmap_options);
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            heatmap_options.borrow_mut().show_arrows = input.checked();
+        })
+    };
+
+    let handle_change_speed = {
+        let animation_speed = Rc::clone(&animation_speed);
+
+        Callback::from(move |event: Event| {
+            let select = event.target_unchecked_into::<HtmlSelectElement>();
+            if let Some(speed) = AnimationSpeed::from_value(&select.value()) {
+                *animation_speed.borrow_mut() = speed;
+            }
+        })
+    };
+
+    let handle_toggle_walking_agent = {
+        let is_walking_agent = Rc::clone(&is_walking_agent);
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            *is_walking_agent.borrow_mut() = input.checked();
+        })
+    };
+
+    let handle_change_tool = {
+        let tool = Rc::clone(&tool);
+        let current_stroke = Rc::clone(&current_stroke);
+
+        Callback::from(move |event: Event| {
+            let select = event.target_unchecked_into::<HtmlSelectElement>();
+            if let Some(selected_tool) = Tool::from_value(&select.value()) {
+                *tool.borrow_mut() = selected_tool;
+                *current_stroke.borrow_mut() = None;
+            }
+        })
+    };
+
+    let handle_change_brush_size = {
+        let brush = Rc::clone(&brush);
+
+        Callback::from(move |event: Event| {
+            let select = event.target_unchecked_into::<HtmlSelectElement>();
+            if let Ok(size) = select.value().parse::<usize>() {
+                brush.borrow_mut().size = size;
+            }
+        })
+    };
+
+    let handle_change_brush_shape = {
+        let brush = Rc::clone(&brush);
+
+        Callback::from(move |event: Event| {
+            let select = event.target_unchecked_into::<HtmlSelectElement>();
+            if let Some(shape) = BrushShape::from_value(&select.value()) {
+                brush.borrow_mut().shape = shape;
+            }
+        })
+    };
+
+    let handle_change_portal_cost = {
+        let portal_cost = Rc::clone(&portal_cost);
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Ok(cost) = input.value().parse::<usize>() {
+                *portal_cost.borrow_mut() = cost.min(MAX_PORTAL_COST);
+            }
+        })
+    };
+
+    let handle_toggle_incremental = {
+        let is_incremental = Rc::clone(&is_incremental);
+        let replan_report = replan_report.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            *is_incremental.borrow_mut() = input.checked();
+            replan_report.set(None);
+        })
+    };
+
+    let handle_toggle_instant_mode = {
+        let is_instant_mode = Rc::clone(&is_instant_mode);
+
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            *is_instant_mode.borrow_mut() = input.checked();
+        })
+    };
+
+    let handle_change_renderer = {
+        let renderer = renderer.clone();
+
+        Callback::from(move |event: Event| {
+            let select = event.target_unchecked_into::<HtmlSelectElement>();
+            if let Some(selected_renderer) = Renderer::from_value(&select.value()) {
+                renderer.set(selected_renderer);
+            }
+        })
+    };
+
+    let handle_change_algorithm = {
+        let algorithm = algorithm.clone();
+
+        Callback::from(move |event: Event| {
+            let select = event.target_unchecked_into::<HtmlSelectElement>();
+            if let Some(selected_algorithm) = Algorithm::from_value(&select.value()) {
+                algorithm.set(selected_algorithm);
+            }
+        })
+    };
+
+    let handle_open_comparison = {
+        let nodes = Rc::clone(&nodes);
+        let current_start_node_id = Rc::clone(&current_start_node_id);
+        let current_end_node_id = Rc::clone(&current_end_node_id);
+        let board_status = Rc::clone(&board_status);
+        let board_view = board_view.clone();
+        let dimensions = *dimensions;
+
+        Callback::from(move |_| {
+            if let BoardStatus::Visualizing = *board_status.borrow() {
+                return;
+            }
+
+            board_view.set(BoardView::Comparison(BoardLayout::capture(
+                &nodes.borrow(),
+                dimensions,
+                current_start_node_id.borrow().unwrap(),
+                current_end_node_id.borrow().unwrap(),
+            )));
+        })
+    };
+
+    let handle_open_benchmark = {
+        let board_status = Rc::clone(&board_status);
+        let board_view = board_view.clone();
+
+        Callback::from(move |_| {
+            if let BoardStatus::Visualizing = *board_status.borrow() {
+                return;
+            }
+
+            board_view.set(BoardView::Benchmark);
+        })
+    };
+
+    let handle_close_view = {
+        let board_view = board_view.clone();
+
+        Callback::from(move |_| {
+            board_view.set(BoardView::Editor);
+        })
+    };
+
+    let handle_wheel = {
+        let viewport_ref = viewport_ref.clone();
+        let board_ref = board_ref.clone();
+        let viewport = Rc::clone(&viewport);
+        let viewport_frame = viewport_frame.clone();
+        let dimensions = *dimensions;
+
+        Callback::from(move |event: WheelEvent| {
+            event.prevent_default();
+            let Some(geometry) = get_board_geometry(&viewport_ref, &board_ref, *viewport.borrow(), dimensions) else {
+                return;
+            };
+
+            let factor = if event.delta_y() < 0.0 { ZOOM_STEP } else { 1.0 / ZOOM_STEP };
+            let x = event.client_x() as f64 - geometry.origin_x;
+            let y = event.client_y() as f64 - geometry.origin_y;
+            let zoomed = BoardGeometry { viewport: viewport.borrow().zoom_at(x, y, factor), ..geometry };
+            *viewport.borrow_mut() = zoomed.viewport;
+            apply_viewport(&board_ref, &zoomed, &viewport_frame);
+        })
+    };
+
+    let handle_pan_start = {
+        let is_space_held = Rc::clone(&is_space_held);
+        let pan_anchor = Rc::clone(&pan_anchor);
+
+        Callback::from(move |event: MouseEvent| {
+            let is_middle_button = event.button() == 1;
+            let is_space_drag = event.button() == 0 && *is_space_held.borrow();
+            if is_middle_button || is_space_drag {
+                // the middle button would otherwise start the browser's autoscroll
+                event.prevent_default();
+                *pan_anchor.borrow_mut() = Some((event.client_x(), event.client_y()));
+            }
+        })
+    };
+
+    let handle_pan_move = {
+        let viewport_ref = viewport_ref.clone();
+        let board_ref = board_ref.clone();
+        let viewport = Rc::clone(&viewport);
+        let viewport_frame = viewport_frame.clone();
+        let pan_anchor = Rc::clone(&pan_anchor);
+        let dimensions = *dimensions;
+
+        Callback::from(move |event: MouseEvent| {
+            let Some((anchor_x, anchor_y)) = *pan_anchor.borrow() else {
+                return;
+            };
+            let Some(geometry) = get_board_geometry(&viewport_ref, &board_ref, *viewport.borrow(), dimensions) else {
+                return;
+            };
+
+            let dx = (event.client_x() - anchor_x) as f64;
+            let dy = (event.client_y() - anchor_y) as f64;
+            let panned = BoardGeometry { viewport: geometry.viewport.pan(dx, dy), ..geometry };
+            *viewport.borrow_mut() = panned.viewport;
+            *pan_anchor.borrow_mut() = Some((event.client_x(), event.client_y()));
+            apply_viewport(&board_ref, &panned, &viewport_frame);
+        })
+    };
+
+    let handle_pan_end = {
+        let pan_anchor = Rc::clone(&pan_anchor);
+
+        Callback::from(move |_: MouseEvent| {
+            *pan_anchor.borrow_mut() = None;
+        })
+    };
+
+    let handle_fit_to_screen = {
+        let viewport_ref = viewport_ref.clone();
+        let board_ref = board_ref.clone();
+        let viewport = Rc::clone(&viewport);
+        let viewport_frame = viewport_frame.clone();
+        let dimensions = *dimensions;
+
+        Callback::from(move |_| {
+            let Some(geometry) = get_board_geometry(&viewport_ref, &board_ref, *viewport.borrow(), dimensions) else {
+                return;
+            };
+
+            let fitted = Viewport::fit(geometry.board_width, geometry.board_height, geometry.view_width, geometry.view_height);
+            let fitted = BoardGeometry { viewport: fitted, ..geometry };
+            *viewport.borrow_mut() = fitted.viewport;
+            apply_viewport(&board_ref, &fitted, &viewport_frame);
+        })
+    };
+
+    let handle_jump_to = {
+        let viewport_ref = viewport_ref.clone();
+        let board_ref = board_ref.clone();
+        let viewport = Rc::clone(&viewport);
+        let viewport_frame = viewport_frame.clone();
+        let dimensions = *dimensions;
+
+        Callback::from(move |(x, y): (f64, f64)| {
+            let Some(geometry) = get_board_geometry(&viewport_ref, &board_ref, *viewport.borrow(), dimensions) else {
+                return;
+            };
+
+            let centered = geometry.viewport.centered_on(x * geometry.board_width, y * geometry.board_height, geometry.view_width, geometry.view_height);
+            let centered = BoardGeometry { viewport: centered, ..geometry };
+            *viewport.borrow_mut() = centered.viewport;
+            apply_viewport(&board_ref, &centered, &viewport_frame);
+        })
+    };
+
+    let handle_load_map = {
+        let nodes = nodes.clone();
+        let board_ref = board_ref.clone();
+        let viewport = Rc::clone(&viewport);
+        let viewport_frame = viewport_frame.clone();
+        let dimensions = dimensions.clone();
+        let renderer = renderer.clone();
+        let current_start_node_id = Rc::clone(&current_start_node_id);
+        let current_end_node_id = Rc::clone(&current_end_node_id);
+        let board_status = Rc::clone(&board_status);
+        let search_state = search_state.clone();
+        let hovered_node_id = hovered_node_id.clone();
+
+        Callback::from(move |map: MovingAiMap| {
+            let mut board_status_borrow = board_status.borrow_mut();
+            if let BoardStatus::Visualizing = *board_status_borrow {
+                return;
+            }
+
+            // scenarios bring their own endpoints, so the first and last open cells are only a starting point;
+            // `movingai::parse_map` turns away maps without two of them, and the panel shows why
+            let mut open_node_ids = map.walls.iter().enumerate().filter(|(_, &is_wall)| !is_wall).map(|(node_id, _)| node_id);
+            let (Some(start_node_id), Some(end_node_id)) = (open_node_ids.next(), open_node_ids.next_back()) else {
+                return;
+            };
+
+            reset_search_state(&nodes, &search_state);
+            hovered_node_id.set(None);
+
+            let grid_nodes = map
+                .walls
+                .iter()
+                .enumerate()
+                .map(|(node_id, &is_wall)| {
+                    let node_status = match node_id {
+                        id if id == start_node_id => NodeStatus::Start,
+                        id if id == end_node_id => NodeStatus::End,
+                        _ if is_wall => NodeStatus::Off,
+                        _ => NodeStatus::On,
+                    };
+                    GridNode::build(node_id, node_status, DEFAULT_WEIGHT)
+                })
+                .collect();
+            *current_start_node_id.borrow_mut() = Some(start_node_id);
+            *current_end_node_id.borrow_mut() = Some(end_node_id);
+            nodes.set(Rc::new(RefCell::new(grid_nodes)));
+            dimensions.set(map.dimensions);
+            *viewport.borrow_mut() = Viewport::default();
+            if let Some(board_element) = board_ref.cast::<HtmlElement>() {
+                let _ = board_element.set_attribute("style", &Viewport::default().get_transform_style());
+            }
+            viewport_frame.set(ViewportFrame::default());
+            if map.dimensions.get_num_nodes() > MAX_DOM_RENDERER_CELLS {
+                renderer.set(Renderer::Canvas);
+            }
+
+            *board_status_borrow = BoardStatus::NotVisualized;
+        })
+    };
+
+    let handle_trace_path = {
+        let nodes = Rc::clone(&nodes);
+        let last_run = Rc::clone(&last_run);
+        let traced_path = Rc::clone(&traced_path);
+        let hovered_node_id = hovered_node_id.clone();
+        let is_tracing = is_tracing.clone();
+
+        Callback::from(move |_| {
+            clear_traced_path(&nodes, &traced_path);
+
+            if *is_tracing {
+                is_tracing.set(false);
+                return;
+            }
+
+            let (Some(node_id), Some(tree)) = (*hovered_node_id, last_run.borrow().clone()) else {
+                return;
+            };
+
+            let nodes_borrow = nodes.borrow();
+            let mut traced_path_borrow = traced_path.borrow_mut();
+            let mut current_node_id = Some(node_id);
+            while let Some(id) = current_node_id {
+                if let Some(node_ref) = nodes_borrow[id].node_ref.cast::<HtmlElement>() {
+                    utils::add_trace_highlight(&node_ref);
+                }
+                traced_path_borrow.push(id);
+                current_node_id = tree.previous[id];
+            }
+
+            is_tracing.set(true);
+        })
+    };
+
+    let hovered_cell = hovered_node_id.map(|node_id| {
+        CellInfo::build(node_id, &nodes.borrow()[node_id], dimensions.columns, last_run.borrow().as_ref())
+    });
+
+    // the editor stays mounted while comparing so its painted cells survive the round trip
+    let editor_class = if *board_view == BoardView::Editor { "flex gap-20 justify-center items-center bg-gray-900 min-h-screen w-screen py-8" } else { "hidden" };
+
     html! {
         <>
-            <div class="flex gap-20 justify-center items-center bg-gray-900 h-screen w-screen">
+            if let BoardView::Comparison(layout) = (*board_view).clone() {
+                <div class="flex justify-center items-center bg-gray-900 min-h-screen w-screen">
+                    <Comparison layout={layout} speed={*animation_speed.borrow()} on_close={handle_close_view.clone()} />
+                </div>
+            }
+            if let BoardView::Benchmark = *board_view {
+                <div class="flex justify-center items-center bg-gray-900 min-h-screen w-screen">
+                    <BenchmarkPanel dimensions={*dimensions} on_close={handle_close_view.clone()} />
+                </div>
+            }
+            <div class={editor_class}>
                 <div
-                   class="grid gap-0.25 grid-cols-50 grid-rows-50 bg-gray-900 h-400 w-400 lg:h-600 lg:w-600 2xl:h-800 2xl:w-800 border-2 border-teal-700 "
-                   onclick={&handle_mouse_action}
-                   oncontextmenu={&handle_mouse_action}
-                   onmouseover={&handle_mouse_action}
-                   ondragstart={&handle_drag_start}
-                   >
-                   {nodes.borrow().iter().map(|GridNode {node, ..}| node.clone()).collect::<Html>()}
+                    ref={viewport_ref}
+                    class="relative overflow-hidden border-2 border-teal-700 bg-gray-900 h-400 w-400 lg:h-600 lg:w-600 2xl:h-800 2xl:w-800"
+                    ondragover={Callback::from(|event: DragEvent| event.prevent_default())}
+                    ondrop={handle_drop_image}
+                    onwheel={handle_wheel}
+                    onmousedown={handle_pan_start}
+                    onmousemove={handle_pan_move}
+                    onmouseup={handle_pan_end.clone()}
+                    onmouseleave={handle_pan_end}
+                    >
+                    <div ref={board_ref} class="relative w-full h-full" style={viewport.borrow().get_transform_style()}>
+                        if *renderer == Renderer::Canvas {
+                            <CanvasGrid nodes={Rc::clone(&nodes)} dimensions={*dimensions} on_mouse_action={(*handle_mouse_action).clone()} />
+                        } else {
+                            // keyed by the nodes so a loaded map mounts fresh cells instead of patching the painted ones
+                            <Grid key={Rc::as_ptr(&*nodes) as usize} nodes={Rc::clone(&nodes)} dimensions={*dimensions} on_mouse_action={(*handle_mouse_action).clone()} />
+                        }
+                        <MarkerLayer nodes={Rc::clone(&nodes)} dimensions={*dimensions} />
+                    </div>
                 </div>
 
                 <div class="flex flex-col gap-20">
                     <div class="flex flex-col gap-6">   
+                        <select class="bg-gray-800 text-white p-2 rounded-md" onchange={handle_change_algorithm}>
+                            {ALGORITHMS.iter().map(|option| html! {
+                                <option value={option.get_value()} selected={*option == *algorithm}>{option.get_name()}</option>
+                            }).collect::<Html>()}
+                        </select>
                         <button class="text-white border-2 border-green-600 p-2 rounded-md hover:bg-green-600 hover:text-black" onclick={handle_create_adjacency_list}>{"Find shortest Path"}</button>
+                        <button class="text-white border-2 border-sky-700 p-2 rounded-md hover:bg-sky-700 hover:text-black" onclick={handle_show_shortest_path_tree}>{"Shortest Path Tree"}</button>
+                        <div class="flex justify-center gap-4 text-zinc-400">
+                            <label class="flex items-center gap-2"><input type="checkbox" onchange={handle_toggle_distances} />{"Distances"}</label>
+                            <label class="flex items-center gap-2"><input type="checkbox" onchange={handle_toggle_arrows} />{"Arrows"}</label>
+                        </div>
                         <button class="text-white border-2 border-emerald-900 p-2 rounded-md hover:bg-emerald-900 hover:text-black" onclick={handle_clear_traversed_nodes}>{"Clear Path"}</button>
+                        <label class="flex justify-center items-center gap-2 text-zinc-400">{"Speed"}
+                            <select class="bg-gray-800 text-white p-1 rounded-md" onchange={handle_change_speed}>
+                                <option value="fast" selected=true>{"fast"}</option>
+                                <option value="medium">{"medium"}</option>
+                                <option value="slow">{"slow"}</option>
+                                <option value="line">{"line by line"}</option>
+                            </select>
+                        </label>
+                        <label class="flex justify-center items-center gap-2 text-zinc-400" title="after the search an agent walks the path, slower on heavier cells">
+                            <input type="checkbox" onchange={handle_toggle_walking_agent} />{"Walk the path"}
+                        </label>
+                        <label class="flex justify-center items-center gap-2 text-zinc-400" title="the canvas has no heatmap labels or path tracing">{"Renderer"}
+                            <select class="bg-gray-800 text-white p-1 rounded-md" onchange={handle_change_renderer}>
+                                {RENDERERS.iter().map(|option| html! {
+                                    <option value={option.get_value()} selected={*option == *renderer}>{option.get_name()}</option>
+                                }).collect::<Html>()}
+                            </select>
+                        </label>
+                        <label class="flex justify-center items-center gap-2 text-zinc-400" title="edits on a finished run solve it again straight away">
+                            <input type="checkbox" onchange={handle_toggle_instant_mode} />{"Instant mode"}
+                        </label>
+                        <label class="flex justify-center items-center gap-2 text-zinc-400" title="in instant mode, repair the last search with LPA* instead of searching again">
+                            <input type="checkbox" onchange={handle_toggle_incremental} />{"Replan incrementally"}
+                        </label>
+                        <label class="flex justify-center items-center gap-2 text-zinc-400" title="Ctrl + drag draws walls, Shift + drag clears them">{"Tool"}
+                            <select class="bg-gray-800 text-white p-1 rounded-md" onchange={handle_change_tool}>
+                                {TOOLS.iter().map(|option| html! {
+                                    <option value={option.get_value()} selected={*option == *tool.borrow()}>{option.get_name()}</option>
+                                }).collect::<Html>()}
+                            </select>
+                        </label>
+                        <label class="flex justify-center items-center gap-2 text-zinc-400" title="how many cells across each stroke paints">{"Brush"}
+                            <select class="bg-gray-800 text-white p-1 rounded-md" onchange={handle_change_brush_size}>
+                                {BRUSH_SIZES.iter().map(|size| html! {
+                                    <option value={size.to_string()} selected={*size == brush.borrow().size}>{size}</option>
+                                }).collect::<Html>()}
+                            </select>
+                            <select class="bg-gray-800 text-white p-1 rounded-md" onchange={handle_change_brush_shape}>
+                                {BRUSH_SHAPES.iter().map(|shape| html! {
+                                    <option value={shape.get_value()} selected={*shape == brush.borrow().shape}>{shape.get_value()}</option>
+                                }).collect::<Html>()}
+                            </select>
+                        </label>
+                        <label class="flex justify-center items-center gap-2 text-zinc-400" title="what a jump costs between the portal pairs drawn from now on">{"Portal cost"}
+                            <input
+                                class="w-14 bg-gray-800 text-white p-1 rounded-md"
+                                type="number"
+                                min="0"
+                                max={MAX_PORTAL_COST.to_string()}
+                                value={DEFAULT_PORTAL_COST.to_string()}
+                                onchange={handle_change_portal_cost}
+                                />
+                        </label>
+                        <button
+                            class="text-white border-2 border-teal-700 p-2 rounded-md hover:bg-teal-700 hover:text-black"
+                            title="wheel zooms, middle button or space + drag pans"
+                            onclick={handle_fit_to_screen}
+                            >
+                            {"Fit to screen"}
+                        </button>
+                        <div class="flex flex-col gap-2 p-2 rounded-md border-2 border-zinc-700 text-zinc-400">
+                            <div class="flex justify-center gap-3">
+                                <label class="flex items-center gap-1">{"Walls %"}
+                                    <input class="w-14 bg-gray-800 text-white p-1 rounded-md" type="number" min="0" max="100" value={(scatter_options.wall_density * 100.0).round().to_string()} onchange={handle_change_scatter_density} />
+                                </label>
+                                <label class="flex items-center gap-1">{"Seed"}
+                                    <input class="w-16 bg-gray-800 text-white p-1 rounded-md" type="number" min="0" value={scatter_options.seed.to_string()} onchange={handle_change_scatter_seed} />
+                                </label>
+                            </div>
+                            <label class="flex justify-center items-center gap-2"><input type="checkbox" checked={scatter_options.ensure_path} onchange={handle_toggle_ensure_path} />{"Guarantee a path"}</label>
+                            <button class="text-white border-2 border-stone-500 p-2 rounded-md hover:bg-stone-500 hover:text-black" onclick={handle_scatter_walls}>{"Scatter walls"}</button>
+                            if let Some(error) = (*scatter_error).clone() {
+                                <p class="text-xs text-red-500">{error}</p>
+                            }
+                        </div>
+                        <div class="flex flex-col gap-2 p-2 rounded-md border-2 border-zinc-700 text-zinc-400">
+                            <div class="flex justify-center gap-3">
+                                <label class="flex items-center gap-1">{"Scale"}
+                                    <input class="w-14 bg-gray-800 text-white p-1 rounded-md" type="number" min="1" value={terrain_options.scale.to_string()} onchange={handle_change_terrain_scale} />
+                                </label>
+                                <label class="flex items-center gap-1">{"Octaves"}
+                                    <input class="w-12 bg-gray-800 text-white p-1 rounded-md" type="number" min="1" max="6" value={terrain_options.octaves.to_string()} onchange={handle_change_terrain_octaves} />
+                                </label>
+                            </div>
+                            <div class="flex justify-center gap-3">
+                                <label class="flex items-center gap-1">{"Seed"}
+                                    <input class="w-16 bg-gray-800 text-white p-1 rounded-md" type="number" min="0" value={terrain_options.seed.to_string()} onchange={handle_change_terrain_seed} />
+                                </label>
+                                <label class="flex items-center gap-1" title="cells higher than this become walls, leave empty for none">{"Walls above %"}
+                                    <input class="w-14 bg-gray-800 text-white p-1 rounded-md" type="number" min="0" max="100" value={terrain_options.wall_threshold.map_or(String::new(), |threshold| (threshold * 100.0).round().to_string())} onchange={handle_change_terrain_walls} />
+                                </label>
+                            </div>
+                            <button class="text-white border-2 border-stone-400 p-2 rounded-md hover:bg-stone-400 hover:text-black" onclick={handle_generate_terrain}>{"Generate terrain"}</button>
+                        </div>
+                        <div class="flex flex-col gap-2 p-2 rounded-md border-2 border-zinc-700 text-zinc-400">
+                            <label class="flex flex-col gap-1" title="or drop a PNG onto the board">{"Import PNG map"}
+                                <input class="text-xs" type="file" accept="image/png" onchange={handle_choose_image} />
+                            </label>
+                            <label class="flex items-center gap-2"><input type="checkbox" checked=true onchange={handle_toggle_image_markers} />{"Red/green pixels mark end/start"}</label>
+                            if let Some(error) = (*import_error).clone() {
+                                <p class="text-xs text-red-500">{error}</p>
+                            }
+                        </div>
+                        <div class="flex flex-col gap-2 p-2 rounded-md border-2 border-zinc-700 text-zinc-400">
+                            <label class="flex justify-center items-center gap-2">{"Export cell size (px)"}
+                                <input class="w-14 bg-gray-800 text-white p-1 rounded-md" type="number" min="1" max="64" value={export::DEFAULT_CELL_SIZE.to_string()} onchange={handle_change_export_cell_size} />
+                            </label>
+                            <div class="flex justify-center gap-2">
+                                <button class="text-white border-2 border-teal-700 p-2 rounded-md hover:bg-teal-700 hover:text-black" onclick={handle_export_svg}>{"Export SVG"}</button>
+                                <button class="text-white border-2 border-teal-700 p-2 rounded-md hover:bg-teal-700 hover:text-black" onclick={handle_export_png}>{"Export PNG"}</button>
+                            </div>
+                            <div class="flex justify-center items-center gap-2">
+                                <label class="flex items-center gap-1">{"FPS"}
+                                    <input class="w-12 bg-gray-800 text-white p-1 rounded-md" type="number" min="1" max="50" value={recording::DEFAULT_FRAMES_PER_SECOND.to_string()} onchange={handle_change_recording_fps} />
+                                </label>
+                                <button class="text-white border-2 border-teal-700 p-2 rounded-md hover:bg-teal-700 hover:text-black" title="records the selected algorithm at the selected speed" onclick={handle_record_gif}>{"Record GIF"}</button>
+                            </div>
+                            if let Some(error) = (*export_error).clone() {
+                                <p class="text-xs text-red-500">{error}</p>
+                            }
+                        </div>
+                        <button class="text-white border-2 border-violet-700 p-2 rounded-md hover:bg-violet-700 hover:text-black" onclick={handle_open_comparison}>{"Compare algorithms"}</button>
+                        <button class="text-white border-2 border-amber-600 p-2 rounded-md hover:bg-amber-600 hover:text-black" onclick={handle_open_benchmark}>{"Benchmark"}</button>
                         <button class="text-white border-2 border-red-800 p-2 rounded-md hover:bg-red-800 hover:text-black" onclick={handle_reset_board}>{"Reset board"}</button>
                     </div>
 
@@ -266,8 +1701,29 @@ pub fn Board() -> Html {
                         <p>{"Right Click: "}<span class="pl-4">{"set "}</span><span class="text-red-700">{"end"}</span><span>{" node"}</span></p>
                         <p>{"Ctrl + Click: "}<span class="pl-4">{"deactivate "}</span><span>{"node"}</span></p>
                         <p>{"Shift + Click: "}<span class="pl-4">{"activate "}</span><span>{"node"}</span></p>
+                        <p>{"Drag start / end: "}<span class="pl-4">{"move it, re-solving a finished run"}</span></p>
+                        <p>{"Ctrl / Shift + Drag: "}<span class="pl-4">{"draw with the tool"}</span></p>
+                        <p>{"One-way tools: "}<span class="pl-4">{"cells only crossed along the arrow"}</span></p>
+                        <p>{"Portal tool + Ctrl + Drag: "}<span class="pl-4">{"link the two ends"}</span></p>
+                        <p>{"Wheel: "}<span class="pl-4">{"zoom"}</span></p>
+                        <p>{"Middle / Space + Drag: "}<span class="pl-4">{"pan"}</span></p>
+                        <p><span class="text-stone-400">{"lighter"}</span><span>{" cells cost more to enter"}</span></p>
+                        <p><span class="text-cyan-500">{"frontier"}</span><span>{" (open set) / "}</span><span class="text-blue-700">{"settled"}</span><span>{" (closed set)"}</span></p>
+                        <p><span class="text-fuchsia-500">{"agent"}</span><span>{" in the "}</span><span class="text-zinc-600">{"fog"}</span><span>{", finding "}</span><span class="text-orange-600">{"walls"}</span></p>
                     </div>
                 </div>
+
+                <div class="flex flex-col gap-6">
+                    <Minimap nodes={Rc::clone(&nodes)} dimensions={*dimensions} frame={*viewport_frame} on_jump={handle_jump_to} />
+                    <Inspector cell={hovered_cell} is_tracing={*is_tracing} on_trace_path={handle_trace_path} />
+                    <QueuePanel snapshot={(*queue_snapshot).clone()} columns={dimensions.columns} />
+                    if let Some(report) = (*replan_report).clone() {
+                        <ReplanPanel report={report} on_measure_full_replan={handle_measure_full_replan} />
+                    }
+                    <FogPanel progress={*fog_progress} on_explore={handle_explore_fog} />
+                    <PseudocodePanel current_line={*pseudocode_line} algorithm={*algorithm} />
+                    <ScenarioPanel nodes={Rc::clone(&nodes)} dimensions={*dimensions} algorithm={*algorithm} on_load_map={handle_load_map} />
+                </div>
             </div>
         </>
     }
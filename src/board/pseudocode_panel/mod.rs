@@ -0,0 +1,27 @@
+use yew::prelude::*;
+
+use crate::dijkstras::{Algorithm, PSEUDOCODE};
+
+#[derive(Properties, PartialEq)]
+pub struct PseudocodePanelProps {
+    pub current_line: Option<usize>,
+    pub algorithm: Algorithm,
+}
+
+#[function_component]
+pub fn PseudocodePanel(props: &PseudocodePanelProps) -> Html {
+    html! {
+        <div class="flex flex-col w-80 p-3 rounded-md border-2 border-zinc-700 text-zinc-400">
+            <p class="mb-1">{props.algorithm.get_name()}</p>
+            <pre class="text-xs">
+                {PSEUDOCODE.iter().enumerate().map(|(line, code)| {
+                    let line_class = if Some(line) == props.current_line { "block bg-amber-400 text-black" } else { "block" };
+                    html! {
+                        <code class={line_class}>{format!("{:>2}  {}", line + 1, code)}</code>
+                    }
+                }).collect::<Html>()}
+            </pre>
+            <p class="mt-1 text-xs text-zinc-500">{props.algorithm.get_priority_formula()}</p>
+        </div>
+    }
+}
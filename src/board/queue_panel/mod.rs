@@ -0,0 +1,62 @@
+use yew::prelude::*;
+
+use crate::dijkstras::QueueReplay;
+
+const MAX_SHOWN_ENTRIES: usize = 12;
+
+#[derive(Clone, Default, PartialEq)]
+pub struct QueueSnapshot {
+    pub entries: Vec<(usize, usize)>,
+    pub size: usize,
+    pub next_node_id: Option<usize>,
+}
+
+impl QueueSnapshot {
+    pub fn build(queue_replay: &QueueReplay, next_node_id: Option<usize>) -> Self {
+        let mut entries = queue_replay.get_sorted_entries();
+        entries.truncate(MAX_SHOWN_ENTRIES);
+
+        QueueSnapshot {
+            entries,
+            size: queue_replay.len(),
+            next_node_id,
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct QueuePanelProps {
+    pub snapshot: QueueSnapshot,
+    pub columns: usize,
+}
+
+#[function_component]
+pub fn QueuePanel(props: &QueuePanelProps) -> Html {
+    let QueuePanelProps { snapshot, columns } = props;
+    let hidden_entries = snapshot.size.saturating_sub(snapshot.entries.len());
+
+    html! {
+        <div class="flex flex-col gap-1 w-56 p-3 rounded-md border-2 border-zinc-700 text-zinc-400">
+            <p>{"Priority queue: "}<span class="text-white">{snapshot.size}</span></p>
+            <table class="text-sm">
+                <thead>
+                    <tr class="text-zinc-500"><th class="text-left">{"cell"}</th><th class="text-right">{"key"}</th></tr>
+                </thead>
+                <tbody>
+                    {snapshot.entries.iter().map(|&(node_id, key)| {
+                        let row_class = if Some(node_id) == snapshot.next_node_id { "bg-cyan-500 text-black" } else { "text-white" };
+                        html! {
+                            <tr class={row_class}>
+                                <td>{format!("({}, {})", node_id / columns, node_id % columns)}</td>
+                                <td class="text-right">{key}</td>
+                            </tr>
+                        }
+                    }).collect::<Html>()}
+                </tbody>
+            </table>
+            if hidden_entries > 0 {
+                <p class="text-zinc-500 text-sm">{format!("+{} more", hidden_entries)}</p>
+            }
+        </div>
+    }
+}
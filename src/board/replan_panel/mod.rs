@@ -0,0 +1,43 @@
+use yew::prelude::*;
+
+use crate::lpa_star::ReplanReport;
+
+#[derive(Properties, PartialEq)]
+pub struct ReplanPanelProps {
+    pub report: ReplanReport,
+    pub on_measure_full_replan: Callback<MouseEvent>,
+}
+
+fn get_share(saved: usize, full: usize) -> f64 {
+    match full {
+        0 => 0.0,
+        full => saved as f64 / full as f64 * 100.0,
+    }
+}
+
+/// How much of the previous search the last incremental replan could keep.
+#[function_component]
+pub fn ReplanPanel(props: &ReplanPanelProps) -> Html {
+    let report = &props.report;
+
+    html! {
+        <div class="flex flex-col gap-1 w-56 p-3 rounded-md border-2 border-zinc-700 text-zinc-400">
+            <p>{"Incremental replan (LPA*)"}</p>
+            <p class="text-sm">{"Changed cells: "}<span class="text-white">{report.changed_cells}</span></p>
+            <p class="text-sm">{"Re-expanded: "}<span class="text-white">{report.expanded.len()}</span></p>
+            if let (Some(full), Some(saved)) = (report.full_replan_expansions, report.get_saved_expansions()) {
+                <p class="text-sm">{"From scratch: "}<span class="text-white">{full}</span></p>
+                <p class="text-sm">
+                    {"Saved: "}<span class="text-white">{format!("{} ({:.0}%)", saved, get_share(saved, full))}</span>
+                </p>
+            } else {
+                // a search from scratch on every edit would undo what replanning saves, so it waits to be asked for
+                <button class="text-sm text-white border-2 border-zinc-500 p-1 rounded-md hover:bg-zinc-500 hover:text-black" onclick={props.on_measure_full_replan.clone()}>
+                    {"Compare with a search from scratch"}
+                </button>
+            }
+            <p class="text-sm">{"Cost: "}<span class="text-white">{report.cost.map_or("no path".to_string(), |cost| cost.to_string())}</span></p>
+            <p class="text-xs text-zinc-500">{"The searched cells shown are only the ones this edit re-expanded."}</p>
+        </div>
+    }
+}
@@ -0,0 +1,158 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gloo::file::callbacks::FileReader;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::dijkstras::Algorithm;
+use crate::movingai::{self, MovingAiMap, Scenario, ScenarioReport};
+
+use super::{GridDimensions, Nodes};
+
+/// Only the first mismatches are listed; the summary still counts all of them.
+const MAX_LISTED_MISMATCHES: usize = 20;
+
+#[derive(Properties)]
+pub struct ScenarioPanelProps {
+    pub nodes: Nodes,
+    pub dimensions: GridDimensions,
+    pub algorithm: Algorithm,
+    pub on_load_map: Callback<MovingAiMap>,
+}
+
+impl PartialEq for ScenarioPanelProps {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.nodes, &other.nodes)
+            && self.dimensions == other.dimensions
+            && self.algorithm == other.algorithm
+            && self.on_load_map == other.on_load_map
+    }
+}
+
+/// Reads the chosen file as text and hands it to `on_text`; the read is cancelled if its reader is dropped.
+fn read_text_file(event: Event, reader: &RefCell<Option<FileReader>>, on_text: Callback<String>) {
+    let input = event.target_unchecked_into::<HtmlInputElement>();
+    if let Some(file) = input.files().and_then(|files| files.get(0)) {
+        let file = gloo::file::File::from(file);
+        *reader.borrow_mut() = Some(gloo::file::callbacks::read_as_text(&file, move |result| {
+            if let Ok(text) = result {
+                on_text.emit(text);
+            }
+        }));
+    }
+    input.set_value("");
+}
+
+#[function_component]
+pub fn ScenarioPanel(props: &ScenarioPanelProps) -> Html {
+    let map_reader = use_mut_ref(|| Option::<FileReader>::None);
+    let scenario_reader = use_mut_ref(|| Option::<FileReader>::None);
+    let scenarios = use_state(Vec::<Scenario>::new);
+    let report = use_state(|| Option::<ScenarioReport>::None);
+    let error = use_state_eq(|| Option::<String>::None);
+
+    let handle_choose_map = {
+        let on_load_map = props.on_load_map.clone();
+        let report = report.clone();
+        let error = error.clone();
+
+        let on_text = Callback::from(move |text: String| match movingai::parse_map(&text) {
+            Ok(map) => {
+                error.set(None);
+                report.set(None);
+                on_load_map.emit(map);
+            }
+            Err(parse_error) => error.set(Some(format!("map {}", parse_error))),
+        });
+
+        Callback::from(move |event: Event| read_text_file(event, &map_reader, on_text.clone()))
+    };
+
+    let handle_choose_scenarios = {
+        let scenarios = scenarios.clone();
+        let report = report.clone();
+        let error = error.clone();
+
+        let on_text = Callback::from(move |text: String| match movingai::parse_scenarios(&text) {
+            Ok(parsed_scenarios) => {
+                error.set(None);
+                report.set(None);
+                scenarios.set(parsed_scenarios);
+            }
+            Err(parse_error) => error.set(Some(format!("scenarios {}", parse_error))),
+        });
+
+        Callback::from(move |event: Event| read_text_file(event, &scenario_reader, on_text.clone()))
+    };
+
+    let handle_run_scenarios = {
+        let nodes = Rc::clone(&props.nodes);
+        let dimensions = props.dimensions;
+        let algorithm = props.algorithm;
+        let scenarios = scenarios.clone();
+        let report = report.clone();
+
+        Callback::from(move |_| {
+            report.set(movingai::run_scenarios(&nodes.borrow(), dimensions, &scenarios, algorithm));
+        })
+    };
+
+    html! {
+        <div class="flex flex-col gap-2 w-56 p-3 rounded-md border-2 border-zinc-700 text-zinc-400">
+            <p>{"MovingAI benchmark"}</p>
+            <label class="flex flex-col gap-1 text-sm">{"Map (.map)"}
+                <input class="text-xs" type="file" accept=".map" onchange={handle_choose_map} />
+            </label>
+            <label class="flex flex-col gap-1 text-sm">{"Scenarios (.scen)"}
+                <input class="text-xs" type="file" accept=".scen" onchange={handle_choose_scenarios} />
+            </label>
+            <button
+                class="text-white border-2 border-amber-600 p-1 rounded-md hover:bg-amber-600 hover:text-black disabled:opacity-40"
+                disabled={scenarios.is_empty() || !props.algorithm.is_optimal()}
+                onclick={handle_run_scenarios}
+                >
+                {format!("Run {} scenarios", scenarios.len())}
+            </button>
+            <p class="text-xs text-zinc-500">{"A reference check: scenarios are solved by a separate search the benchmark's way, with 8-way moves, √2 diagonals and no cutting past a wall's corner, not by the board's 4-way solver."}</p>
+            if !props.algorithm.is_optimal() {
+                <p class="text-xs text-zinc-500">{format!("{} does not promise optimal lengths, pick Dijkstra or A* to check them.", props.algorithm.get_name())}</p>
+            }
+            if let Some(message) = (*error).clone() {
+                <p class="text-xs text-red-500">{message}</p>
+            }
+            if let Some(report) = (*report).clone() {
+                <p>
+                    {"Checked: "}<span class="text-white">{report.checked}</span>
+                    {", mismatches: "}<span class="text-white">{report.mismatches.len()}</span>
+                    if report.skipped > 0 {
+                        {format!(", skipped {} for another map size", report.skipped)}
+                    }
+                    if report.blocked > 0 {
+                        {format!(", skipped {} starting or ending on a wall", report.blocked)}
+                    }
+                </p>
+                if !report.mismatches.is_empty() {
+                    <table class="text-sm">
+                        <thead>
+                            <tr class="text-zinc-500">
+                                <th class="text-left">{"#"}</th>
+                                <th class="text-right">{"optimal"}</th>
+                                <th class="text-right">{"found"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {report.mismatches.iter().take(MAX_LISTED_MISMATCHES).map(|mismatch| html! {
+                                <tr class="text-white" title={format!("bucket {}: {:?} to {:?}", mismatch.scenario.bucket, mismatch.scenario.start, mismatch.scenario.goal)}>
+                                    <td>{mismatch.index}</td>
+                                    <td class="text-right">{format!("{:.2}", mismatch.scenario.optimal_length)}</td>
+                                    <td class="text-right">{mismatch.found_length.map_or("no path".to_string(), |length| format!("{:.2}", length))}</td>
+                                </tr>
+                            }).collect::<Html>()}
+                        </tbody>
+                    </table>
+                }
+            }
+        </div>
+    }
+}
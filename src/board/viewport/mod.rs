@@ -0,0 +1,223 @@
+use super::GridDimensions;
+
+pub const MIN_SCALE: f64 = 0.5;
+pub const MAX_SCALE: f64 = 64.0;
+/// How much one wheel notch zooms in or out.
+pub const ZOOM_STEP: f64 = 1.15;
+
+/// The zoom and pan applied to the board: a board point `p` is drawn at `p * scale + offset`
+/// inside the viewport.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub scale: f64,
+    pub offset_x: f64,
+    pub offset_y: f64,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            scale: 1.0,
+            offset_x: 0.0,
+            offset_y: 0.0,
+        }
+    }
+}
+
+impl Viewport {
+    pub fn to_board(self, x: f64, y: f64) -> (f64, f64) {
+        ((x - self.offset_x) / self.scale, (y - self.offset_y) / self.scale)
+    }
+
+    /// Zooms by `factor` while keeping the board point under `(x, y)` where it is.
+    pub fn zoom_at(&self, x: f64, y: f64, factor: f64) -> Self {
+        let scale = (self.scale * factor).clamp(MIN_SCALE, MAX_SCALE);
+        let (board_x, board_y) = self.to_board(x, y);
+
+        Viewport {
+            scale,
+            offset_x: x - board_x * scale,
+            offset_y: y - board_y * scale,
+        }
+    }
+
+    pub fn pan(&self, dx: f64, dy: f64) -> Self {
+        Viewport {
+            offset_x: self.offset_x + dx,
+            offset_y: self.offset_y + dy,
+            ..*self
+        }
+    }
+
+    /// Scales the whole board to fit the view and centers it.
+    pub fn fit(board_width: f64, board_height: f64, view_width: f64, view_height: f64) -> Self {
+        let scale = (view_width / board_width.max(1.0))
+            .min(view_height / board_height.max(1.0))
+            .clamp(MIN_SCALE, MAX_SCALE);
+
+        Viewport {
+            scale,
+            offset_x: (view_width - board_width * scale) / 2.0,
+            offset_y: (view_height - board_height * scale) / 2.0,
+        }
+    }
+
+    /// Keeps the zoom and moves the board so `(board_x, board_y)` is in the middle of the view.
+    pub fn centered_on(self, board_x: f64, board_y: f64, view_width: f64, view_height: f64) -> Self {
+        Viewport {
+            offset_x: view_width / 2.0 - board_x * self.scale,
+            offset_y: view_height / 2.0 - board_y * self.scale,
+            ..self
+        }
+    }
+
+    pub fn get_transform_style(&self) -> String {
+        format!(
+            "transform: translate({}px, {}px) scale({}); transform-origin: 0 0;",
+            self.offset_x, self.offset_y, self.scale
+        )
+    }
+}
+
+/// The part of the board in view, as fractions of its width and height.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewportFrame {
+    pub left: f64,
+    pub top: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Default for ViewportFrame {
+    fn default() -> Self {
+        ViewportFrame {
+            left: 0.0,
+            top: 0.0,
+            width: 1.0,
+            height: 1.0,
+        }
+    }
+}
+
+impl ViewportFrame {
+    pub fn get_style(&self) -> String {
+        format!(
+            "left: {}%; top: {}%; width: {}%; height: {}%;",
+            self.left * 100.0,
+            self.top * 100.0,
+            self.width * 100.0,
+            self.height * 100.0,
+        )
+    }
+}
+
+/// Where the board sits on screen at the moment of a pointer event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoardGeometry {
+    /// Screen position of the viewport's top left corner, inside its border.
+    pub origin_x: f64,
+    pub origin_y: f64,
+    pub viewport: Viewport,
+    /// Untransformed size of the board in CSS pixels.
+    pub board_width: f64,
+    pub board_height: f64,
+    /// Inner size of the viewport the board is drawn in.
+    pub view_width: f64,
+    pub view_height: f64,
+    pub dimensions: GridDimensions,
+}
+
+impl BoardGeometry {
+    /// The cell under a pointer at `(client_x, client_y)`, if it is over the board at all.
+    pub fn get_node_id(&self, client_x: f64, client_y: f64) -> Option<usize> {
+        let (board_x, board_y) = self.viewport.to_board(client_x - self.origin_x, client_y - self.origin_y);
+        if board_x < 0.0 || board_y < 0.0 || board_x >= self.board_width || board_y >= self.board_height {
+            return None;
+        }
+
+        let column = (board_x / self.board_width * self.dimensions.columns as f64) as usize;
+        let row = (board_y / self.board_height * self.dimensions.rows as f64) as usize;
+
+        Some(row.min(self.dimensions.rows - 1) * self.dimensions.columns + column.min(self.dimensions.columns - 1))
+    }
+
+    /// The visible part of the board, cut to the board's edges.
+    pub fn get_visible_frame(&self) -> ViewportFrame {
+        let (left, top) = self.viewport.to_board(0.0, 0.0);
+        let (right, bottom) = self.viewport.to_board(self.view_width, self.view_height);
+        let clamp_x = |x: f64| (x / self.board_width.max(1.0)).clamp(0.0, 1.0);
+        let clamp_y = |y: f64| (y / self.board_height.max(1.0)).clamp(0.0, 1.0);
+
+        ViewportFrame {
+            left: clamp_x(left),
+            top: clamp_y(top),
+            width: clamp_x(right) - clamp_x(left),
+            height: clamp_y(bottom) - clamp_y(top),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_geometry(viewport: Viewport) -> BoardGeometry {
+        BoardGeometry {
+            origin_x: 100.0,
+            origin_y: 50.0,
+            viewport,
+            board_width: 400.0,
+            board_height: 200.0,
+            view_width: 400.0,
+            view_height: 400.0,
+            dimensions: GridDimensions { columns: 20, rows: 10 },
+        }
+    }
+
+    #[test]
+    fn zooming_keeps_the_point_under_the_pointer() {
+        let viewport = Viewport::default().pan(30.0, -10.0).zoom_at(120.0, 80.0, 2.0);
+
+        assert_eq!(viewport.scale, 2.0);
+        let (board_x, board_y) = viewport.to_board(120.0, 80.0);
+        assert!((board_x - 90.0).abs() < 1e-9 && (board_y - 90.0).abs() < 1e-9);
+        assert_eq!(Viewport::default().zoom_at(0.0, 0.0, 1000.0).scale, MAX_SCALE);
+    }
+
+    #[test]
+    fn fit_centers_the_board() {
+        let viewport = Viewport::fit(400.0, 200.0, 800.0, 800.0);
+
+        assert_eq!(viewport, Viewport { scale: 2.0, offset_x: 0.0, offset_y: 200.0 });
+    }
+
+    #[test]
+    fn pointer_maps_to_cells_under_the_transform() {
+        let plain = get_geometry(Viewport::default());
+        assert_eq!(plain.get_node_id(100.0, 50.0), Some(0));
+        assert_eq!(plain.get_node_id(499.0, 249.0), Some(199));
+        assert_eq!(plain.get_node_id(99.0, 60.0), None);
+        assert_eq!(plain.get_node_id(510.0, 60.0), None);
+
+        // zoomed 4x towards the bottom right, one cell is now 80px across
+        let zoomed = get_geometry(Viewport { scale: 4.0, offset_x: -1200.0, offset_y: -600.0 });
+        assert_eq!(zoomed.get_node_id(100.0, 50.0), Some(7 * 20 + 15));
+        assert_eq!(zoomed.get_node_id(100.0 + 81.0, 50.0 + 79.0), Some(8 * 20 + 16));
+    }
+
+    #[test]
+    fn visible_frame_is_cut_to_the_board() {
+        assert_eq!(get_geometry(Viewport::default()).get_visible_frame(), ViewportFrame::default());
+
+        let frame = get_geometry(Viewport { scale: 4.0, offset_x: -1200.0, offset_y: -600.0 }).get_visible_frame();
+        assert_eq!(frame, ViewportFrame { left: 0.75, top: 0.75, width: 0.25, height: 0.25 });
+    }
+
+    #[test]
+    fn centering_keeps_the_zoom() {
+        let viewport = Viewport { scale: 2.0, offset_x: 0.0, offset_y: 0.0 }.centered_on(100.0, 50.0, 400.0, 400.0);
+
+        assert_eq!(viewport, Viewport { scale: 2.0, offset_x: 0.0, offset_y: 100.0 });
+        assert_eq!(viewport.to_board(200.0, 200.0), (100.0, 50.0));
+    }
+}
@@ -1,19 +1,274 @@
-use priority_queue::{self, DoublePriorityQueue};
+use priority_queue::DoublePriorityQueue;
 
-use crate::board::GridNode;
-use crate::utils;
+use crate::board::{GridDimensions, GridNode, Portal};
 use crate::{adjacency_list, adjacency_list::AdjacencyEntry};
 
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Algorithm {
+    Dijkstra,
+    AStar,
+    GreedyBestFirst,
+}
+
+pub const ALGORITHMS: [Algorithm; 3] = [Algorithm::Dijkstra, Algorithm::AStar, Algorithm::GreedyBestFirst];
+
+impl Algorithm {
+    pub fn from_value(value: &str) -> Option<Self> {
+        ALGORITHMS.into_iter().find(|algorithm| algorithm.get_value() == value)
+    }
+
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            Algorithm::Dijkstra => "dijkstra",
+            Algorithm::AStar => "a-star",
+            Algorithm::GreedyBestFirst => "greedy",
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Algorithm::Dijkstra => "Dijkstra",
+            Algorithm::AStar => "A*",
+            Algorithm::GreedyBestFirst => "Greedy best-first",
+        }
+    }
+
+    pub fn get_priority_formula(&self) -> &'static str {
+        match self {
+            Algorithm::Dijkstra => "priority(v) = dist[v]",
+            Algorithm::AStar => "priority(v) = dist[v] + h(v)",
+            Algorithm::GreedyBestFirst => "priority(v) = h(v)",
+        }
+    }
+
+    /// Whether the algorithm always finds a shortest path; greedy best-first trades that for speed.
+    pub fn is_optimal(&self) -> bool {
+        !matches!(self, Algorithm::GreedyBestFirst)
+    }
+
+    fn get_priority(&self, distance: usize, heuristic: usize) -> usize {
+        match self {
+            Algorithm::Dijkstra => distance,
+            Algorithm::AStar => distance + heuristic,
+            Algorithm::GreedyBestFirst => heuristic,
+        }
+    }
+}
+
+pub fn get_manhattan_distance(from: usize, to: usize, dimensions: GridDimensions) -> usize {
+    let (from_row, from_col) = (dimensions.get_row(from), dimensions.get_column(from));
+    let (to_row, to_col) = (dimensions.get_row(to), dimensions.get_column(to));
+
+    from_row.abs_diff(to_row) + from_col.abs_diff(to_col)
+}
+
+/// The Manhattan distance to the goal, lowered wherever a portal could make the trip cheaper. Every
+/// move costs at least one and every jump at least its portal's cost, so it never overestimates and
+/// stays consistent, and A* keeps finding optimal paths on boards with portals.
+pub struct Heuristic {
+    goal_node_id: usize,
+    dimensions: GridDimensions,
+    /// Each portal cell with a lower bound on the cost from it to the goal.
+    portal_bounds: Vec<(usize, usize)>,
+}
+
+impl Heuristic {
+    pub fn new(nodes: &[GridNode], dimensions: GridDimensions, goal_node_id: usize) -> Self {
+        let portals: Vec<(usize, Portal)> = nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(node_id, grid_node)| grid_node.portal.map(|portal| (node_id, portal)))
+            .collect();
+
+        // Dijkstra backwards from the goal over the portal cells alone, walking between any two of
+        // them for their Manhattan distance or jumping between partners
+        let mut bounds: Vec<usize> = portals.iter().map(|&(node_id, _)| get_manhattan_distance(node_id, goal_node_id, dimensions)).collect();
+        let mut is_settled = vec![false; portals.len()];
+        while let Some(settled) = (0..portals.len()).filter(|&index| !is_settled[index]).min_by_key(|&index| bounds[index]) {
+            is_settled[settled] = true;
+            let settled_node_id = portals[settled].0;
+
+            (0..portals.len()).filter(|&index| !is_settled[index]).for_each(|index| {
+                let (node_id, portal) = portals[index];
+                let walk = get_manhattan_distance(node_id, settled_node_id, dimensions);
+                let step = if portal.partner == settled_node_id { walk.min(portal.cost) } else { walk };
+                bounds[index] = bounds[index].min(step + bounds[settled]);
+            });
+        }
+
+        Heuristic {
+            goal_node_id,
+            dimensions,
+            portal_bounds: portals.iter().map(|&(node_id, _)| node_id).zip(bounds).collect(),
+        }
+    }
+
+    pub fn get(&self, node_id: usize) -> usize {
+        self.portal_bounds
+            .iter()
+            .map(|&(portal_node_id, bound)| get_manhattan_distance(node_id, portal_node_id, self.dimensions) + bound)
+            .fold(get_manhattan_distance(node_id, self.goal_node_id, self.dimensions), usize::min)
+    }
+}
+
 pub struct DijkstrasTraversal {
     path: Vec<usize>,
+    /// The settled nodes in order, leaving out start and end.
+    #[cfg(test)]
     traversed_nodes: Vec<usize>,
+    cost: Option<usize>,
+    steps: Vec<TraversalStep>,
+    shortest_path_tree: ShortestPathTree,
 }
 
 pub struct DijkstrasResult {
     distance: usize,
+    distances: Vec<usize>,
     previous: Vec<Option<usize>>,
     visited_ordered: Vec<usize>,
+    steps: Vec<TraversalStep>,
+}
+
+/// One pop from the priority queue: the node that got settled and the nodes
+/// whose tentative distance it improved, which join the frontier paired with
+/// their new key. A pop of an already settled node is stale and opens nothing.
+/// `events` lists everything the pop did, in order, one per pseudocode line
+/// run; the first step also holds the start's push and the last one the final
+/// check of an emptied queue.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraversalStep {
+    pub node_id: usize,
+    pub opened: Vec<(usize, usize)>,
+    pub events: Vec<TraversalEvent>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraversalEvent {
+    PushStart { node_id: usize, key: usize },
+    CheckQueue { is_empty: bool },
+    Pop { node_id: usize, key: usize },
+    SkipStale { node_id: usize },
+    CheckGoal { node_id: usize, is_goal: bool },
+    ScanNeighbors { node_id: usize },
+    Relax { from: usize, to: usize, distance: usize, key: usize, improved: bool },
+    SetDistance { node_id: usize, distance: usize },
+    SetPrevious { node_id: usize, previous: usize },
+    Push { node_id: usize, key: usize },
+}
+
+pub const PSEUDOCODE: [&str; 10] = [
+    "dist[start] ← 0; push(start, priority(start))",
+    "while queue is not empty:",
+    "    u, key ← pop_min()",
+    "    if u is settled: continue",
+    "    if u = goal: return path",
+    "    for each neighbor v of u:",
+    "        if dist[u] + w(u, v) < dist[v]:",
+    "            dist[v] ← dist[u] + w(u, v)",
+    "            prev[v] ← u",
+    "            push(v, priority(v))",
+];
+
+impl TraversalEvent {
+    /// Index into `PSEUDOCODE` of the line this event executes.
+    pub fn get_pseudocode_line(&self) -> usize {
+        match self {
+            TraversalEvent::PushStart { .. } => 0,
+            TraversalEvent::CheckQueue { .. } => 1,
+            TraversalEvent::Pop { .. } => 2,
+            TraversalEvent::SkipStale { .. } => 3,
+            TraversalEvent::CheckGoal { .. } => 4,
+            TraversalEvent::ScanNeighbors { .. } => 5,
+            TraversalEvent::Relax { .. } => 6,
+            TraversalEvent::SetDistance { .. } => 7,
+            TraversalEvent::SetPrevious { .. } => 8,
+            TraversalEvent::Push { .. } => 9,
+        }
+    }
+}
+
+/// Rebuilds the contents of the priority queue by replaying traversal events,
+/// so the heap state after any step is available without storing a copy per step.
+/// A node can sit in the queue more than once, the older entries going stale.
+#[derive(Default)]
+pub struct QueueReplay {
+    entries: Vec<(usize, usize)>,
+}
+
+impl QueueReplay {
+    pub fn apply_event(&mut self, event: &TraversalEvent) {
+        match *event {
+            TraversalEvent::Pop { node_id, key } => {
+                if let Some(index) = self.entries.iter().position(|&entry| entry == (node_id, key)) {
+                    self.entries.swap_remove(index);
+                }
+            }
+            TraversalEvent::PushStart { node_id, key } | TraversalEvent::Push { node_id, key } => {
+                self.entries.push((node_id, key));
+            }
+            _ => {}
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get_sorted_entries(&self) -> Vec<(usize, usize)> {
+        let mut entries = self.entries.clone();
+        entries.sort_by_key(|&(node_id, key)| (key, node_id));
+        entries
+    }
+}
+
+#[derive(Clone)]
+pub struct ShortestPathTree {
+    pub distances: Vec<usize>,
+    pub previous: Vec<Option<usize>>,
+    pub settled_ordered: Vec<usize>,
+}
+
+impl ShortestPathTree {
+    pub fn max_distance(&self) -> usize {
+        self.distances
+            .iter()
+            .filter(|&&distance| distance != usize::MAX)
+            .max()
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn is_settled(&self, node_id: usize) -> bool {
+        self.settled_ordered.contains(&node_id)
+    }
+
+    pub fn path_to(&self, start_node_id: usize, end_node_id: usize) -> Vec<usize> {
+        if self.distances[end_node_id] == usize::MAX {
+            return Vec::new();
+        }
+
+        build_path(&self.previous, start_node_id, end_node_id)
+    }
+}
+
+fn build_path(previous: &[Option<usize>], start_node_id: usize, end_node_id: usize) -> Vec<usize> {
+    let mut path = Vec::new();
+    let mut current_node_id = end_node_id;
+
+    while current_node_id != start_node_id {
+        if let Some(previous_node_id) = previous[current_node_id] {
+            path.push(previous_node_id);
+            current_node_id = previous_node_id;
+        } else {
+            break;
+        }
+    }
+
+    path.pop();
+    path.reverse();
+
+    path
 }
 
 impl DijkstrasTraversal {
@@ -21,59 +276,113 @@ impl DijkstrasTraversal {
         self.path.clone()
     }
 
+    #[cfg(test)]
     pub fn get_traversed_nodes(&self) -> Vec<usize> {
         self.traversed_nodes.clone()
     }
 
+    pub fn get_cost(&self) -> Option<usize> {
+        self.cost
+    }
+
+    pub fn get_visited_count(&self) -> usize {
+        self.shortest_path_tree.settled_ordered.len()
+    }
+
+    pub fn get_steps(&self) -> Vec<TraversalStep> {
+        self.steps.clone()
+    }
+
+    pub fn get_shortest_path_tree(&self) -> ShortestPathTree {
+        self.shortest_path_tree.clone()
+    }
+
+    #[cfg(test)]
     pub fn run(
-        nodes: &Vec<GridNode>,
+        nodes: &[GridNode],
+        dimensions: GridDimensions,
         start_node_id: usize,
         end_node_id: usize
         ) -> Self {
+        Self::run_with_algorithm(nodes, dimensions, start_node_id, end_node_id, Algorithm::Dijkstra)
+    }
+
+    pub fn run_with_algorithm(
+        nodes: &[GridNode],
+        dimensions: GridDimensions,
+        start_node_id: usize,
+        end_node_id: usize,
+        algorithm: Algorithm,
+        ) -> Self {
         let num_nodes = nodes.len();
         assert!(end_node_id < num_nodes, "End node ID is out of bounds");
         assert!(start_node_id < num_nodes, "Start node ID is out of bounds");
 
-        let adjacency_list = adjacency_list::create_adjacency_list(&nodes);
-        let mut result = Self::dijkstras(adjacency_list, start_node_id, end_node_id);
+        let adjacency_list = adjacency_list::create_adjacency_list(nodes, dimensions);
+        let heuristic = Heuristic::new(nodes, dimensions, end_node_id);
+        let priority = |node_id: usize, distance: usize| algorithm.get_priority(distance, heuristic.get(node_id));
+        let result = Self::search(adjacency_list, start_node_id, Some(end_node_id), priority);
 
-        utils::drop_first_and_last(&mut result.visited_ordered);
+        let (path, cost) = match result.distance {
+            usize::MAX => (Vec::new(), None),
+            distance => (build_path(&result.previous, start_node_id, end_node_id), Some(distance)),
+        };
 
-        if result.distance == usize::MAX {
-            return Self {
-                path: Vec::new(),
-                traversed_nodes: result.visited_ordered,
-            };
+        Self {
+            path,
+            #[cfg(test)]
+            traversed_nodes: result
+                .visited_ordered
+                .iter()
+                .copied()
+                .filter(|&node_id| node_id != start_node_id && node_id != end_node_id)
+                .collect(),
+            cost,
+            steps: result.steps,
+            shortest_path_tree: ShortestPathTree {
+                distances: result.distances,
+                previous: result.previous,
+                settled_ordered: result.visited_ordered,
+            },
         }
+    }
 
-        let mut path = Vec::new();
-        let mut current_node_id = end_node_id;
-
-        while current_node_id != start_node_id {
-            if let Some(previous_node_id) = result.previous[current_node_id] {
-                path.push(previous_node_id);
-                current_node_id = previous_node_id;
-            } else {
-                break;
-            }
-        }
+    pub fn run_to_exhaustion(nodes: &[GridNode], dimensions: GridDimensions, start_node_id: usize) -> ShortestPathTree {
+        assert!(start_node_id < nodes.len(), "Start node ID is out of bounds");
 
-        path.pop();
-        path.reverse();
+        let adjacency_list = adjacency_list::create_adjacency_list(nodes, dimensions);
+        let result = Self::search(adjacency_list, start_node_id, None, |_, distance| distance);
 
-        Self {
-            path,
-            traversed_nodes: result.visited_ordered,
+        ShortestPathTree {
+            distances: result.distances,
+            previous: result.previous,
+            settled_ordered: result.visited_ordered,
         }
     }
 
+    #[cfg(test)]
     fn dijkstras(
         adjacency_list: Vec<AdjacencyEntry>,
         start_node_id: usize,
         end_node_id: usize,
         ) -> DijkstrasResult {
-        let mut heap: DoublePriorityQueue<usize, usize> = priority_queue::DoublePriorityQueue::new();
-        heap.push(start_node_id, 0);
+        Self::search(adjacency_list, start_node_id, Some(end_node_id), |_, distance| distance)
+    }
+
+    fn search(
+        adjacency_list: Vec<AdjacencyEntry>,
+        start_node_id: usize,
+        end_node_id: Option<usize>,
+        priority: impl Fn(usize, usize) -> usize,
+        ) -> DijkstrasResult {
+        // improving a node pushes it again rather than re-keying it, and the
+        // outdated entry is skipped once it reaches the top; entries are told
+        // apart by push count, which leaves ties in the queue's own order
+        let mut heap: DoublePriorityQueue<(usize, usize), usize> = DoublePriorityQueue::new();
+        let mut pushes = 0;
+        let start_key = priority(start_node_id, 0);
+        heap.push((start_node_id, pushes), start_key);
+        let mut pending_events = vec![TraversalEvent::PushStart { node_id: start_node_id, key: start_key }];
 
         let mut distances = vec![usize::MAX; adjacency_list.len()];
         distances[start_node_id] = 0;
@@ -81,43 +390,82 @@ impl DijkstrasTraversal {
         let mut visited = vec![false; adjacency_list.len()];
         let mut previous: Vec<Option<usize>> = vec![None; adjacency_list.len()];
         let mut visited_ordered = Vec::new();
+        let mut steps = Vec::new();
+
+        while let Some(((node_id, _), key)) = heap.pop_min() {
+            pending_events.push(TraversalEvent::CheckQueue { is_empty: false });
+            pending_events.push(TraversalEvent::Pop { node_id, key });
+            steps.push(TraversalStep {
+                node_id,
+                opened: Vec::new(),
+                events: std::mem::take(&mut pending_events),
+            });
+            let step = steps.last_mut().unwrap();
 
-        while let Some((node_id, min_distance)) = heap.pop_min() {
-            visited[node_id] = true;
-            visited_ordered.push(node_id);
-            if min_distance > distances[node_id] {
+            if visited[node_id] {
+                step.events.push(TraversalEvent::SkipStale { node_id });
                 continue;
-            } else {
-                distances[node_id] = min_distance;
             }
+            visited[node_id] = true;
+            visited_ordered.push(node_id);
+
+            let min_distance = distances[node_id];
 
-            if node_id == end_node_id {
+            let is_goal = Some(node_id) == end_node_id;
+            step.events.push(TraversalEvent::CheckGoal { node_id, is_goal });
+
+            if is_goal {
                 return DijkstrasResult {
                     distance: distances[node_id],
+                    distances,
                     previous,
                     visited_ordered,
+                    steps,
                 };
             }
 
+            step.events.push(TraversalEvent::ScanNeighbors { node_id });
             adjacency_list[node_id].edges.iter().for_each(|edge| {
                 if visited[edge.to] {
                     return;
                 }
 
                 let new_distance = min_distance + edge.weight;
-
-                if new_distance < distances[edge.to] {
+                let new_key = priority(edge.to, new_distance);
+                let improved = new_distance < distances[edge.to];
+                step.events.push(TraversalEvent::Relax {
+                    from: node_id,
+                    to: edge.to,
+                    distance: new_distance,
+                    key: new_key,
+                    improved,
+                });
+
+                if improved {
                     distances[edge.to] = new_distance;
                     previous[edge.to] = Some(node_id);
-                    heap.push(edge.to, new_distance);
+                    pushes += 1;
+                    heap.push((edge.to, pushes), new_key);
+                    step.opened.push((edge.to, new_key));
+                    step.events.extend([
+                        TraversalEvent::SetDistance { node_id: edge.to, distance: new_distance },
+                        TraversalEvent::SetPrevious { node_id: edge.to, previous: node_id },
+                        TraversalEvent::Push { node_id: edge.to, key: new_key },
+                    ]);
                 }
             });
         }
 
+        if let Some(last_step) = steps.last_mut() {
+            last_step.events.push(TraversalEvent::CheckQueue { is_empty: true });
+        }
+
         DijkstrasResult {
             distance: usize::MAX,
+            distances,
             previous,
             visited_ordered,
+            steps,
         }
     }
 }
@@ -129,12 +477,13 @@ mod tests {
     use super::*;
     use crate::adjacency_list;
     use crate::tests_common;
+    use crate::NodeStatus;
 
     #[test]
     fn dijkstras_returns_correct_result() {
         let mock_grid = tests_common::get_mock_grid();
         let mock_node_statuses = tests_common::get_mock_nodes(mock_grid);
-        let adjacency_list = adjacency_list::create_adjacency_list(&mock_node_statuses);
+        let adjacency_list = adjacency_list::create_adjacency_list(&mock_node_statuses, tests_common::MOCK_DIMENSIONS);
         let expected_distance = 22;
         let start_node_id = 0;
         let end_node_id = 8;
@@ -160,7 +509,7 @@ mod tests {
         let start_node_id = 0;
         let end_node_id = 8;
 
-        let result = DijkstrasTraversal::run(&mock_node_statuses, start_node_id, end_node_id);
+        let result = DijkstrasTraversal::run(&mock_node_statuses, tests_common::MOCK_DIMENSIONS, start_node_id, end_node_id);
         let path = result.path;
         let visited_ordered = result.traversed_nodes;
 
@@ -182,9 +531,252 @@ mod tests {
         let start_node_id = 0;
         let end_node_id = 8;
 
-        let result = DijkstrasTraversal::run(&mock_node_statuses, start_node_id, end_node_id);
+        let result = DijkstrasTraversal::run(&mock_node_statuses, tests_common::MOCK_DIMENSIONS, start_node_id, end_node_id);
         let path = result.path;
 
         assert!(path.is_empty());
     }
+
+    #[test]
+    fn run_to_exhaustion_settles_every_reachable_node() {
+        let mock_grid = tests_common::get_mock_grid();
+        let mock_node_statuses = tests_common::get_mock_nodes(mock_grid);
+        let expected_path = vec![
+            10, 20, 30, 40, 50, 60, 61, 62, 63, 64, 65, 66, 56, 46, 47, 48, 49, 39, 29, 19, 9,
+        ];
+        let start_node_id = 0;
+        let end_node_id = 8;
+
+        let tree = DijkstrasTraversal::run_to_exhaustion(&mock_node_statuses, tests_common::MOCK_DIMENSIONS, start_node_id);
+
+        assert_eq!(tree.distances[end_node_id], 22);
+        assert_eq!(tree.path_to(start_node_id, end_node_id), expected_path);
+        assert_eq!(tree.previous[start_node_id], None);
+        assert_eq!(tree.settled_ordered[0], start_node_id);
+        mock_node_statuses
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.node_status == NodeStatus::Off)
+            .for_each(|(node_id, _)| assert_eq!(tree.distances[node_id], usize::MAX));
+    }
+
+    #[test]
+    fn run_to_exhaustion_leaves_unreachable_nodes_without_predecessor() {
+        let mock_grid = tests_common::get_mock_grid_with_unreachable_end();
+        let mock_node_statuses = tests_common::get_mock_nodes(mock_grid);
+        let start_node_id = 0;
+        let end_node_id = 8;
+
+        let tree = DijkstrasTraversal::run_to_exhaustion(&mock_node_statuses, tests_common::MOCK_DIMENSIONS, start_node_id);
+
+        assert_eq!(tree.distances[end_node_id], usize::MAX);
+        assert_eq!(tree.previous[end_node_id], None);
+        assert!(tree.path_to(start_node_id, end_node_id).is_empty());
+    }
+
+    #[test]
+    fn steps_follow_settle_order_and_open_improved_neighbors() {
+        let mock_grid = tests_common::get_mock_grid();
+        let mock_node_statuses = tests_common::get_mock_nodes(mock_grid);
+        let start_node_id = 0;
+        let end_node_id = 8;
+
+        let result = DijkstrasTraversal::run(&mock_node_statuses, tests_common::MOCK_DIMENSIONS, start_node_id, end_node_id);
+        let steps = result.get_steps();
+        let settled_ordered: Vec<usize> = steps.iter().map(|step| step.node_id).collect();
+
+        assert_eq!(steps[0].node_id, 0);
+        assert_eq!(steps[0].opened, vec![(10, 1)]);
+        assert_eq!(steps[1].node_id, 10);
+        assert_eq!(steps[1].opened, vec![(11, 2), (20, 2)]);
+        assert_eq!(settled_ordered, result.shortest_path_tree.settled_ordered);
+        assert_eq!(result.get_traversed_nodes(), settled_ordered[1..settled_ordered.len() - 1]);
+        assert_eq!(steps.last().unwrap().node_id, end_node_id);
+        steps.iter().enumerate().for_each(|(index, step)| {
+            step.opened.iter().for_each(|(opened_node_id, _)| {
+                assert!(!settled_ordered[..=index].contains(opened_node_id));
+            });
+        });
+    }
+
+    #[test]
+    fn queue_replay_pops_minimum_key_at_every_step() {
+        let mock_grid = tests_common::get_mock_grid();
+        let mock_node_statuses = tests_common::get_mock_nodes(mock_grid);
+
+        let result = DijkstrasTraversal::run(&mock_node_statuses, tests_common::MOCK_DIMENSIONS, 0, 8);
+        let steps = result.get_steps();
+        let mut replay = QueueReplay::default();
+        steps[0].events.iter().for_each(|event| replay.apply_event(event));
+
+        assert_eq!(replay.get_sorted_entries(), vec![(10, 1)]);
+
+        steps.iter().skip(1).for_each(|step| {
+            let entries = replay.get_sorted_entries();
+            let (_, min_key) = entries[0];
+            let popped_key = entries.iter().find(|(node_id, _)| *node_id == step.node_id).unwrap().1;
+
+            assert_eq!(popped_key, min_key);
+            step.events.iter().for_each(|event| replay.apply_event(event));
+            assert!(replay.get_sorted_entries().iter().all(|(node_id, _)| *node_id != step.node_id));
+        });
+        assert_eq!(replay.len(), replay.get_sorted_entries().len());
+    }
+
+    #[test]
+    fn events_record_each_pseudocode_line_of_a_pop() {
+        let mock_grid = tests_common::get_mock_grid();
+        let mock_node_statuses = tests_common::get_mock_nodes(mock_grid);
+        let end_node_id = 8;
+
+        let result = DijkstrasTraversal::run(&mock_node_statuses, tests_common::MOCK_DIMENSIONS, 0, end_node_id);
+        let steps = result.get_steps();
+        let first_step_lines: Vec<usize> = steps[0]
+            .events
+            .iter()
+            .map(|event| event.get_pseudocode_line())
+            .collect();
+
+        assert_eq!(
+            steps[0].events,
+            vec![
+                TraversalEvent::PushStart { node_id: 0, key: 0 },
+                TraversalEvent::CheckQueue { is_empty: false },
+                TraversalEvent::Pop { node_id: 0, key: 0 },
+                TraversalEvent::CheckGoal { node_id: 0, is_goal: false },
+                TraversalEvent::ScanNeighbors { node_id: 0 },
+                TraversalEvent::Relax { from: 0, to: 10, distance: 1, key: 1, improved: true },
+                TraversalEvent::SetDistance { node_id: 10, distance: 1 },
+                TraversalEvent::SetPrevious { node_id: 10, previous: 0 },
+                TraversalEvent::Push { node_id: 10, key: 1 },
+            ]
+        );
+        assert_eq!(first_step_lines, vec![0, 1, 2, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(
+            steps.last().unwrap().events.last(),
+            Some(&TraversalEvent::CheckGoal { node_id: end_node_id, is_goal: true })
+        );
+        steps.iter().for_each(|step| {
+            let improved: Vec<(usize, usize)> = step
+                .events
+                .iter()
+                .filter_map(|event| match *event {
+                    TraversalEvent::Relax { to, key, improved: true, .. } => Some((to, key)),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(improved, step.opened);
+        });
+    }
+
+    #[test]
+    fn outdated_queue_entries_are_skipped_as_stale() {
+        let edges = |edges: &[(usize, usize)]| AdjacencyEntry {
+            edges: edges.iter().map(|&(to, weight)| adjacency_list::Edge { to, weight }).collect(),
+        };
+        // node 1 is queued at 5 straight from the start, then again at 3 by the detour through 2 and 3
+        let adjacency_list = vec![
+            edges(&[(1, 5), (2, 1)]),
+            edges(&[(4, 5)]),
+            edges(&[(3, 1)]),
+            edges(&[(1, 1)]),
+            edges(&[]),
+        ];
+
+        let result = DijkstrasTraversal::dijkstras(adjacency_list, 0, 4);
+        let stale_step = result
+            .steps
+            .iter()
+            .find(|step| step.events.contains(&TraversalEvent::SkipStale { node_id: 1 }))
+            .unwrap();
+
+        assert_eq!(result.distance, 8);
+        assert_eq!(
+            stale_step.events,
+            vec![TraversalEvent::CheckQueue { is_empty: false }, TraversalEvent::Pop { node_id: 1, key: 5 }, TraversalEvent::SkipStale { node_id: 1 }]
+        );
+        assert_eq!(stale_step.events[2].get_pseudocode_line(), 3);
+        assert!(stale_step.opened.is_empty());
+        assert_eq!(result.visited_ordered, vec![0, 2, 3, 1, 4]);
+        assert_eq!(result.steps.len(), result.visited_ordered.len() + 1);
+
+        // with a stale pop and an improving relax in it, the run goes through every line
+        let mut lines: Vec<usize> = result.steps.iter().flat_map(|step| &step.events).map(|event| event.get_pseudocode_line()).collect();
+        lines.sort();
+        lines.dedup();
+        assert_eq!(lines, (0..PSEUDOCODE.len()).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn a_star_finds_optimal_cost_with_fewer_visits() {
+        let mock_grid = tests_common::get_mock_grid();
+        let mock_node_statuses = tests_common::get_mock_nodes(mock_grid);
+
+        let dijkstra = DijkstrasTraversal::run_with_algorithm(&mock_node_statuses, tests_common::MOCK_DIMENSIONS, 0, 8, Algorithm::Dijkstra);
+        let a_star = DijkstrasTraversal::run_with_algorithm(&mock_node_statuses, tests_common::MOCK_DIMENSIONS, 0, 8, Algorithm::AStar);
+        let greedy = DijkstrasTraversal::run_with_algorithm(&mock_node_statuses, tests_common::MOCK_DIMENSIONS, 0, 8, Algorithm::GreedyBestFirst);
+
+        assert_eq!(dijkstra.get_cost(), Some(22));
+        assert_eq!(a_star.get_cost(), Some(22));
+        assert!(a_star.get_visited_count() <= dijkstra.get_visited_count());
+        assert!(greedy.get_cost().unwrap() >= 22);
+        assert_eq!(greedy.get_path().len() + 1, greedy.get_cost().unwrap());
+    }
+
+    #[test]
+    fn exhausted_queue_ends_with_a_failed_queue_check() {
+        let mock_grid = tests_common::get_mock_grid_with_unreachable_end();
+        let mock_node_statuses = tests_common::get_mock_nodes(mock_grid);
+
+        let result = DijkstrasTraversal::run(&mock_node_statuses, tests_common::MOCK_DIMENSIONS, 0, 8);
+
+        assert_eq!(result.get_steps().last().unwrap().events.last(), Some(&TraversalEvent::CheckQueue { is_empty: true }));
+    }
+
+    #[test]
+    fn every_algorithm_reports_unreachable_end() {
+        let mock_grid = tests_common::get_mock_grid_with_unreachable_end();
+        let mock_node_statuses = tests_common::get_mock_nodes(mock_grid);
+
+        ALGORITHMS.iter().for_each(|&algorithm| {
+            let result = DijkstrasTraversal::run_with_algorithm(&mock_node_statuses, tests_common::MOCK_DIMENSIONS, 0, 8, algorithm);
+
+            assert_eq!(result.get_cost(), None);
+            assert!(result.get_path().is_empty());
+        });
+    }
+
+    #[test]
+    fn a_star_stays_optimal_with_portals() {
+        let mut nodes = tests_common::get_mock_nodes(tests_common::get_mock_grid());
+        // a cheap jump from near the start to near the end, and a second pair that chains onto it
+        [(2, 97, 1), (10, 3, 1)].iter().for_each(|&(first, second, cost)| {
+            nodes[first].portal = Some(Portal { partner: second, cost });
+            nodes[second].portal = Some(Portal { partner: first, cost });
+        });
+
+        let dijkstra = DijkstrasTraversal::run(&nodes, tests_common::MOCK_DIMENSIONS, 0, 99);
+        let a_star = DijkstrasTraversal::run_with_algorithm(&nodes, tests_common::MOCK_DIMENSIONS, 0, 99, Algorithm::AStar);
+        assert_eq!(a_star.get_cost(), dijkstra.get_cost());
+        assert!(a_star.get_cost() < DijkstrasTraversal::run(&tests_common::get_mock_nodes(tests_common::get_mock_grid()), tests_common::MOCK_DIMENSIONS, 0, 99).get_cost());
+
+        // the estimate never exceeds the true remaining cost from any reachable cell
+        let heuristic = Heuristic::new(&nodes, tests_common::MOCK_DIMENSIONS, 99);
+        (0..100).filter(|&node_id| nodes[node_id].node_status != NodeStatus::Off).for_each(|node_id| {
+            if let Some(cost) = DijkstrasTraversal::run(&nodes, tests_common::MOCK_DIMENSIONS, node_id, 99).get_cost() {
+                assert!(heuristic.get(node_id) <= cost, "overestimated at {}", node_id);
+            }
+        });
+        assert_eq!(heuristic.get(2), 1 + get_manhattan_distance(97, 99, tests_common::MOCK_DIMENSIONS));
+    }
+
+    #[test]
+    fn manhattan_distance_counts_rows_and_columns() {
+        let dimensions = tests_common::MOCK_DIMENSIONS;
+
+        assert_eq!(get_manhattan_distance(0, 99, dimensions), 18);
+        assert_eq!(get_manhattan_distance(45, 45, dimensions), 0);
+        assert_eq!(get_manhattan_distance(19, 10, dimensions), 9);
+        assert_eq!(get_manhattan_distance(0, 11, GridDimensions { columns: 4, rows: 3 }), 5);
+    }
 }
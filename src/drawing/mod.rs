@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+
+use crate::board::{Brush, BrushShape, GridDimensions};
+use crate::NodeStatus;
+
+/// The cells on a straight line between two cells, both ends included, by Bresenham's algorithm.
+pub fn get_line(from: usize, to: usize, dimensions: GridDimensions) -> Vec<usize> {
+    let (mut column, mut row) = (dimensions.get_column(from) as isize, dimensions.get_row(from) as isize);
+    let (end_column, end_row) = (dimensions.get_column(to) as isize, dimensions.get_row(to) as isize);
+    let (delta_column, delta_row) = ((end_column - column).abs(), -(end_row - row).abs());
+    let (step_column, step_row) = ((end_column - column).signum(), (end_row - row).signum());
+    let mut error = delta_column + delta_row;
+    let mut line = Vec::new();
+
+    loop {
+        line.push(row as usize * dimensions.columns + column as usize);
+        if column == end_column && row == end_row {
+            return line;
+        }
+
+        let doubled_error = 2 * error;
+        if doubled_error >= delta_row {
+            error += delta_row;
+            column += step_column;
+        }
+        if doubled_error <= delta_column {
+            error += delta_column;
+            row += step_row;
+        }
+    }
+}
+
+/// The cells of the rectangle spanned by two opposite corners; hollow ones keep only the outline.
+pub fn get_rectangle(corner: usize, opposite_corner: usize, dimensions: GridDimensions, is_filled: bool) -> Vec<usize> {
+    let (first_column, last_column) = min_max(dimensions.get_column(corner), dimensions.get_column(opposite_corner));
+    let (first_row, last_row) = min_max(dimensions.get_row(corner), dimensions.get_row(opposite_corner));
+
+    (first_row..=last_row)
+        .flat_map(|row| (first_column..=last_column).map(move |column| (row, column)))
+        .filter(|&(row, column)| {
+            is_filled || row == first_row || row == last_row || column == first_column || column == last_column
+        })
+        .map(|(row, column)| row * dimensions.columns + column)
+        .collect()
+}
+
+fn min_max(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+/// The cells a brush covers around `center`, cut off at the board's edges. Circles keep the cells whose
+/// centers lie within the brush's diameter, so the smallest sizes come out square.
+pub fn get_brush_cells(center: usize, brush: Brush, dimensions: GridDimensions) -> Vec<usize> {
+    let radius = (brush.size / 2) as isize;
+    let (center_row, center_column) = (dimensions.get_row(center) as isize, dimensions.get_column(center) as isize);
+
+    (-radius..=radius)
+        .flat_map(|row_offset| (-radius..=radius).map(move |column_offset| (row_offset, column_offset)))
+        .filter(|&(row_offset, column_offset)| match brush.shape {
+            BrushShape::Square => true,
+            BrushShape::Circle => 4 * (row_offset * row_offset + column_offset * column_offset) <= (2 * radius + 1).pow(2),
+        })
+        .map(|(row_offset, column_offset)| (center_row + row_offset, center_column + column_offset))
+        .filter(|&(row, column)| {
+            (0..dimensions.rows as isize).contains(&row) && (0..dimensions.columns as isize).contains(&column)
+        })
+        .map(|(row, column)| row as usize * dimensions.columns + column as usize)
+        .collect()
+}
+
+/// The cells reachable from `start_node_id` through 4-way neighbors sharing its status, as a bucket fill
+/// would recolor them.
+pub fn get_fill_region(node_statuses: &[NodeStatus], dimensions: GridDimensions, start_node_id: usize) -> Vec<usize> {
+    let region_status = &node_statuses[start_node_id];
+    let mut is_reached = vec![false; node_statuses.len()];
+    let mut queue = VecDeque::from([start_node_id]);
+    let mut region = Vec::new();
+    is_reached[start_node_id] = true;
+
+    while let Some(node_id) = queue.pop_front() {
+        region.push(node_id);
+
+        dimensions.get_neighbors(node_id).for_each(|neighbor| {
+            if !is_reached[neighbor] && node_statuses[neighbor] == *region_status {
+                is_reached[neighbor] = true;
+                queue.push_back(neighbor);
+            }
+        });
+    }
+
+    region
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIMENSIONS: GridDimensions = GridDimensions { columns: 6, rows: 4 };
+
+    #[test]
+    fn lines_have_no_gaps_in_either_direction() {
+        assert_eq!(get_line(0, 5, DIMENSIONS), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(get_line(0, 23, DIMENSIONS), vec![0, 7, 8, 15, 16, 23]);
+        assert_eq!(get_line(23, 0, DIMENSIONS), vec![23, 16, 15, 8, 7, 0]);
+        assert_eq!(get_line(9, 9, DIMENSIONS), vec![9]);
+
+        // consecutive cells always touch, so a fast stroke leaves no holes
+        get_line(18, 3, DIMENSIONS).windows(2).for_each(|pair| {
+            assert!(DIMENSIONS.get_row(pair[0]).abs_diff(DIMENSIONS.get_row(pair[1])) <= 1);
+            assert!(DIMENSIONS.get_column(pair[0]).abs_diff(DIMENSIONS.get_column(pair[1])) <= 1);
+        });
+    }
+
+    #[test]
+    fn hollow_rectangles_keep_only_the_outline() {
+        assert_eq!(get_rectangle(22, 1, DIMENSIONS, false), vec![1, 2, 3, 4, 7, 10, 13, 16, 19, 20, 21, 22]);
+        assert_eq!(get_rectangle(1, 22, DIMENSIONS, true).len(), 16);
+        assert_eq!(get_rectangle(7, 7, DIMENSIONS, false), vec![7]);
+    }
+
+    #[test]
+    fn brushes_are_cut_off_at_the_edges() {
+        let square = Brush { size: 3, shape: BrushShape::Square };
+        assert_eq!(get_brush_cells(7, square, DIMENSIONS), vec![0, 1, 2, 6, 7, 8, 12, 13, 14]);
+        assert_eq!(get_brush_cells(0, square, DIMENSIONS), vec![0, 1, 6, 7]);
+        assert_eq!(get_brush_cells(9, Brush::default(), DIMENSIONS), vec![9]);
+    }
+
+    #[test]
+    fn circular_brushes_drop_the_corners() {
+        let dimensions = GridDimensions::square(9);
+        let circle = |size| Brush { size, shape: BrushShape::Circle };
+
+        assert_eq!(get_brush_cells(40, circle(3), dimensions).len(), 9);
+        let cells = get_brush_cells(40, circle(5), dimensions);
+        assert_eq!(cells.len(), 21);
+        assert!(!cells.contains(&20) && cells.contains(&21) && cells.contains(&22));
+    }
+
+    #[test]
+    fn fill_stops_at_cells_of_another_status() {
+        let mut node_statuses = vec![NodeStatus::On; DIMENSIONS.get_num_nodes()];
+        [2, 8, 14, 20].iter().for_each(|&wall| node_statuses[wall] = NodeStatus::Off);
+        node_statuses[0] = NodeStatus::Start;
+
+        let mut left = get_fill_region(&node_statuses, DIMENSIONS, 1);
+        left.sort();
+        assert_eq!(left, vec![1, 6, 7, 12, 13, 18, 19]);
+
+        assert_eq!(get_fill_region(&node_statuses, DIMENSIONS, 5).len(), 12);
+        assert_eq!(get_fill_region(&node_statuses, DIMENSIONS, 8).len(), 4);
+    }
+}
@@ -0,0 +1,200 @@
+use std::fmt::Write;
+use std::io;
+
+use png::{BitDepth, ColorType, Encoder};
+
+use crate::board::GridNode;
+use crate::utils;
+use crate::{AGENT_COLOR, DEFAULT_COLOR, DISCOVERED_COLOR, END_COLOR, FRONTIER_COLOR, OFF_COLOR, PATH_COLOR, START_COLOR, UNKNOWN_COLOR, VISITED_COLOR, WEIGHT_COLORS};
+
+pub const DEFAULT_CELL_SIZE: usize = 16;
+
+/// Space between cells, matching the grid's 1px gap.
+const GAP: usize = 1;
+const BACKGROUND: [u8; 3] = [0x11, 0x18, 0x27];
+/// Raster exports take a byte per pixel before encoding, so bigger images are refused rather than
+/// running the page out of memory.
+pub const MAX_IMAGE_PIXELS: usize = 100_000_000;
+
+/// RGB values of the Tailwind classes the grid paints cells with.
+const CLASS_COLORS: [(&str, [u8; 3]); 14] = [
+    (DEFAULT_COLOR, [0x37, 0x41, 0x51]),
+    (START_COLOR, [0x15, 0x80, 0x3d]),
+    (END_COLOR, [0xb9, 0x1c, 0x1c]),
+    (OFF_COLOR, [0x11, 0x18, 0x27]),
+    (VISITED_COLOR, [0x1e, 0x40, 0xaf]),
+    (FRONTIER_COLOR, [0x06, 0xb6, 0xd4]),
+    (PATH_COLOR, [0xca, 0x8a, 0x04]),
+    (WEIGHT_COLORS[0], [0x57, 0x53, 0x4e]),
+    (WEIGHT_COLORS[1], [0x78, 0x71, 0x6c]),
+    (WEIGHT_COLORS[2], [0xa8, 0xa2, 0x9e]),
+    (WEIGHT_COLORS[3], [0xd6, 0xd3, 0xd1]),
+    (UNKNOWN_COLOR, [0x27, 0x27, 0x2a]),
+    (DISCOVERED_COLOR, [0xea, 0x58, 0x0c]),
+    (AGENT_COLOR, [0xd9, 0x46, 0xef]),
+];
+
+/// Position of the cell's color in `CLASS_COLORS`, one less than its index in `get_palette`.
+pub fn get_color_index(grid_node: &GridNode) -> usize {
+    let class = utils::get_node_color(&grid_node.node_status, grid_node.weight);
+
+    CLASS_COLORS
+        .iter()
+        .position(|(class_name, _)| *class_name == class)
+        .unwrap_or(0)
+}
+
+fn get_cell_rgb(grid_node: &GridNode) -> [u8; 3] {
+    CLASS_COLORS[get_color_index(grid_node)].1
+}
+
+/// Flat RGB palette for indexed images: the background comes first, then every cell color.
+pub fn get_palette() -> Vec<u8> {
+    std::iter::once(BACKGROUND)
+        .chain(CLASS_COLORS.iter().map(|(_, rgb)| *rgb))
+        .flatten()
+        .collect()
+}
+
+pub fn get_image_size(cell_count: usize, cell_size: usize) -> usize {
+    cell_count * (cell_size + GAP) + GAP
+}
+
+/// The width and height of a raster export, checked against `MAX_IMAGE_PIXELS` before anything is drawn.
+pub fn get_checked_image_size(columns: usize, rows: usize, cell_size: usize) -> io::Result<(usize, usize)> {
+    let (width, height) = (get_image_size(columns, cell_size), get_image_size(rows, cell_size));
+    match width.checked_mul(height) {
+        Some(pixels) if pixels <= MAX_IMAGE_PIXELS => Ok((width, height)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("a {width} x {height} px image is too large, the limit is {} megapixels; try a smaller cell size", MAX_IMAGE_PIXELS / 1_000_000),
+        )),
+    }
+}
+
+pub fn render_svg(nodes: &[GridNode], columns: usize, cell_size: usize) -> String {
+    let rows = nodes.len() / columns;
+    let (width, height) = (get_image_size(columns, cell_size), get_image_size(rows, cell_size));
+    let [r, g, b] = BACKGROUND;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" shape-rendering=\"crispEdges\">\n\
+         <rect width=\"{width}\" height=\"{height}\" fill=\"#{r:02x}{g:02x}{b:02x}\"/>\n",
+    );
+
+    nodes.iter().enumerate().for_each(|(node_id, grid_node)| {
+        let x = GAP + (node_id % columns) * (cell_size + GAP);
+        let y = GAP + (node_id / columns) * (cell_size + GAP);
+        let [r, g, b] = get_cell_rgb(grid_node);
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{cell_size}\" height=\"{cell_size}\" fill=\"#{r:02x}{g:02x}{b:02x}\"/>",
+        );
+    });
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Rasterizes the board into indices of `get_palette`, returning the image width and height with them.
+pub fn render_indexed(nodes: &[GridNode], columns: usize, cell_size: usize) -> (usize, usize, Vec<u8>) {
+    let rows = nodes.len() / columns;
+    let (width, height) = (get_image_size(columns, cell_size), get_image_size(rows, cell_size));
+    let mut pixels = vec![0; width * height];
+
+    nodes.iter().enumerate().for_each(|(node_id, grid_node)| {
+        let x = GAP + (node_id % columns) * (cell_size + GAP);
+        let y = GAP + (node_id / columns) * (cell_size + GAP);
+        // index 0 is the background
+        let color_index = get_color_index(grid_node) as u8 + 1;
+
+        (y..y + cell_size).for_each(|pixel_y| {
+            let row_start = pixel_y * width + x;
+            pixels[row_start..row_start + cell_size].fill(color_index);
+        });
+    });
+
+    (width, height, pixels)
+}
+
+pub fn render_png(nodes: &[GridNode], columns: usize, cell_size: usize) -> Result<Vec<u8>, png::EncodingError> {
+    get_checked_image_size(columns, nodes.len() / columns, cell_size)?;
+    let (width, height, indexed_pixels) = render_indexed(nodes, columns, cell_size);
+    let palette = get_palette();
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = Encoder::new(&mut bytes, width as u32, height as u32);
+        encoder.set_color(ColorType::Indexed);
+        encoder.set_depth(BitDepth::Eight);
+        encoder.set_palette(palette);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&indexed_pixels)?;
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NodeStatus;
+
+    fn get_sample_nodes() -> Vec<GridNode> {
+        vec![
+            GridNode::build_preview(NodeStatus::Start, 1),
+            GridNode::build_preview(NodeStatus::Off, 1),
+            GridNode::build_preview(NodeStatus::On, 9),
+            GridNode::build_preview(NodeStatus::Path, 1),
+            GridNode::build_preview(NodeStatus::Visited, 1),
+            GridNode::build_preview(NodeStatus::End, 1),
+        ]
+    }
+
+    #[test]
+    fn every_cell_class_has_a_color() {
+        let fog_nodes = [NodeStatus::Unknown, NodeStatus::Discovered, NodeStatus::Agent].map(|node_status| GridNode::build_preview(node_status, 1));
+        get_sample_nodes().iter().chain(&fog_nodes).for_each(|grid_node| {
+            let class = utils::get_node_color(&grid_node.node_status, grid_node.weight);
+            assert!(CLASS_COLORS.iter().any(|(class_name, _)| *class_name == class), "{}", class);
+        });
+    }
+
+    #[test]
+    fn svg_has_one_rect_per_cell_plus_background() {
+        let svg = render_svg(&get_sample_nodes(), 3, 10);
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"34\" height=\"23\""));
+        assert_eq!(svg.matches("<rect").count(), 7);
+        assert!(svg.contains("<rect x=\"1\" y=\"1\" width=\"10\" height=\"10\" fill=\"#15803d\"/>"));
+        assert!(svg.contains("<rect x=\"23\" y=\"12\" width=\"10\" height=\"10\" fill=\"#b91c1c\"/>"));
+    }
+
+    #[test]
+    fn png_paints_cells_at_the_chosen_size() {
+        let bytes = render_png(&get_sample_nodes(), 3, 4).unwrap();
+        let mut decoder = png::Decoder::new(&bytes[..]);
+        decoder.set_transformations(png::Transformations::EXPAND);
+        let mut reader = decoder.read_info().unwrap();
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut buffer).unwrap();
+        let get_pixel = |x: usize, y: usize| &buffer[(y * frame.width as usize + x) * 3..][..3];
+
+        assert_eq!((frame.width, frame.height), (16, 11));
+        assert_eq!(get_pixel(0, 0), BACKGROUND);
+        assert_eq!(get_pixel(1, 1), [0x15, 0x80, 0x3d]);
+        assert_eq!(get_pixel(4, 4), [0x15, 0x80, 0x3d]);
+        assert_eq!(get_pixel(5, 5), BACKGROUND);
+        assert_eq!(get_pixel(11, 1), [0xd6, 0xd3, 0xd1]);
+        assert_eq!(get_pixel(1, 6), [0xca, 0x8a, 0x04]);
+    }
+
+    #[test]
+    fn refuses_a_png_too_large_to_render() {
+        let nodes: Vec<GridNode> = (0..500 * 500).map(|_| GridNode::build_preview(NodeStatus::On, 1)).collect();
+
+        let error = render_png(&nodes, 500, 64).unwrap_err();
+
+        assert!(error.to_string().contains("32501 x 32501 px image is too large"), "{}", error);
+        assert!(get_checked_image_size(500, 500, 16).is_ok());
+    }
+}
@@ -0,0 +1,266 @@
+use crate::board::{Brush, BrushShape, Direction, GridDimensions, GridNode, Portal};
+use crate::dijkstras::{Algorithm, DijkstrasTraversal};
+use crate::drawing;
+use crate::{NodeStatus, DEFAULT_WEIGHT};
+
+pub const DEFAULT_SENSOR_RADIUS: usize = 3;
+pub const MAX_SENSOR_RADIUS: usize = 20;
+
+/// Where the agent stands after one move, and what it saw and decided there.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FogStep {
+    pub position: usize,
+    /// Cells the sensor showed for the first time on arriving here.
+    pub revealed: Vec<usize>,
+    /// The route the agent now means to take, goal included and its own cell left out.
+    pub plan: Vec<usize>,
+    /// Whether something just revealed blocked or priced up the previous plan.
+    pub is_replan: bool,
+    /// Cost of every cell entered so far.
+    pub travelled_cost: usize,
+}
+
+/// A walk through the fog from start towards the goal; the first step is the start itself.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FogRun {
+    pub steps: Vec<FogStep>,
+    pub optimal_cost: Option<usize>,
+    pub is_goal_reached: bool,
+}
+
+/// What the agent believes about the board: unknown cells are assumed open both ways at the lowest cost,
+/// and portals are only known of once one of their ends has been seen.
+struct Belief {
+    statuses: Vec<NodeStatus>,
+    weights: Vec<usize>,
+    directions: Vec<Option<Direction>>,
+    portals: Vec<Option<Portal>>,
+    is_known: Vec<bool>,
+}
+
+impl Belief {
+    fn new(num_nodes: usize) -> Self {
+        Belief {
+            statuses: vec![NodeStatus::On; num_nodes],
+            weights: vec![DEFAULT_WEIGHT; num_nodes],
+            directions: vec![None; num_nodes],
+            portals: vec![None; num_nodes],
+            is_known: vec![false; num_nodes],
+        }
+    }
+
+    /// Learns the true state of every unknown cell in sensor range; returns them.
+    fn sense(&mut self, nodes: &[GridNode], dimensions: GridDimensions, position: usize, sensor_radius: usize) -> Vec<usize> {
+        let sensor = Brush { size: 2 * sensor_radius + 1, shape: BrushShape::Circle };
+
+        let revealed: Vec<usize> = drawing::get_brush_cells(position, sensor, dimensions)
+            .into_iter()
+            .filter(|&node_id| !self.is_known[node_id])
+            .collect();
+
+        revealed.iter().for_each(|&node_id| {
+            self.is_known[node_id] = true;
+            self.statuses[node_id] = nodes[node_id].node_status.clone();
+            self.weights[node_id] = nodes[node_id].weight;
+            self.directions[node_id] = nodes[node_id].direction;
+            // seeing one end shows where the jump leads
+            if let Some(portal) = nodes[node_id].portal {
+                self.portals[node_id] = Some(portal);
+                self.portals[portal.partner] = Some(Portal { partner: node_id, cost: portal.cost });
+            }
+        });
+
+        revealed
+    }
+
+    /// Whether a freshly revealed cell turned out other than the agent assumed.
+    fn is_surprise(&self, node_id: usize) -> bool {
+        self.statuses[node_id] == NodeStatus::Off || self.weights[node_id] != DEFAULT_WEIGHT || self.directions[node_id].is_some()
+    }
+
+    fn plan(&self, dimensions: GridDimensions, position: usize, goal_node_id: usize) -> Vec<usize> {
+        let believed_nodes: Vec<GridNode> = self
+            .statuses
+            .iter()
+            .zip(&self.weights)
+            .zip(&self.directions)
+            .zip(&self.portals)
+            .map(|(((node_status, &weight), &direction), &portal)| GridNode {
+                direction,
+                portal,
+                ..GridNode::build_preview(node_status.clone(), weight)
+            })
+            .collect();
+        let dijkstras_traversal = DijkstrasTraversal::run_with_algorithm(&believed_nodes, dimensions, position, goal_node_id, Algorithm::AStar);
+
+        match dijkstras_traversal.get_cost() {
+            Some(_) => dijkstras_traversal.get_path().into_iter().chain([goal_node_id]).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// What one move of the walk costs: entering the cell, or the jump when a portal got the agent there.
+fn get_move_cost(nodes: &[GridNode], dimensions: GridDimensions, from: usize, to: usize) -> usize {
+    let walk_cost = dimensions.get_neighbors(from).any(|neighbor| neighbor == to).then_some(nodes[to].weight);
+    let jump_cost = nodes[from].portal.filter(|portal| portal.partner == to).map(|portal| portal.cost);
+
+    walk_cost.into_iter().chain(jump_cost).min().unwrap_or(nodes[to].weight)
+}
+
+/// Walks an agent that only sees `sensor_radius` cells around it from start to goal. It plans
+/// optimistically through what it has not seen, follows the plan one cell at a time and plans
+/// again whenever its sensor shows the plan is blocked or dearer than assumed. The sensor sees
+/// through walls, so every cell in range is revealed; a wall found only on trying to enter it
+/// is never stepped into and sends the agent back to planning from where it stands.
+pub fn explore(
+    nodes: &[GridNode],
+    dimensions: GridDimensions,
+    start_node_id: usize,
+    goal_node_id: usize,
+    sensor_radius: usize,
+) -> FogRun {
+    let mut belief = Belief::new(nodes.len());
+    let revealed = belief.sense(nodes, dimensions, start_node_id, sensor_radius);
+    let mut plan = belief.plan(dimensions, start_node_id, goal_node_id);
+    let mut steps = vec![FogStep { position: start_node_id, revealed, plan: plan.clone(), is_replan: false, travelled_cost: 0 }];
+    let mut travelled_cost = 0;
+
+    // every replan follows something new being learnt, so the walk always ends
+    let mut previous_position = start_node_id;
+    while !plan.is_empty() {
+        // feel the next cell before entering it, so a wall the sensor missed is bumped into rather than walked through
+        let touched = belief.sense(nodes, dimensions, plan[0], 0);
+        if belief.statuses[plan[0]] == NodeStatus::Off {
+            plan = belief.plan(dimensions, previous_position, goal_node_id);
+            if let Some(step) = steps.last_mut() {
+                step.revealed.extend(touched);
+                step.plan = plan.clone();
+                step.is_replan = true;
+            }
+            continue;
+        }
+
+        let position = plan.remove(0);
+        travelled_cost += get_move_cost(nodes, dimensions, previous_position, position);
+        previous_position = position;
+        if position == goal_node_id {
+            steps.push(FogStep { position, revealed: Vec::new(), plan, is_replan: false, travelled_cost });
+            break;
+        }
+
+        let revealed = belief.sense(nodes, dimensions, position, sensor_radius);
+        // a portal anywhere in sight may open a shortcut, so it is worth planning again too
+        let is_replan = revealed
+            .iter()
+            .any(|node_id| (plan.contains(node_id) && belief.is_surprise(*node_id)) || belief.portals[*node_id].is_some());
+        if is_replan {
+            plan = belief.plan(dimensions, position, goal_node_id);
+        }
+
+        steps.push(FogStep { position, revealed, plan: plan.clone(), is_replan, travelled_cost });
+    }
+
+    let optimal_cost = DijkstrasTraversal::run_with_algorithm(nodes, dimensions, start_node_id, goal_node_id, Algorithm::Dijkstra).get_cost();
+    let is_goal_reached = steps.last().is_some_and(|step| step.position == goal_node_id);
+
+    FogRun { steps, optimal_cost, is_goal_reached }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIMENSIONS: GridDimensions = GridDimensions::square(7);
+
+    fn get_nodes(walls: &[usize]) -> Vec<GridNode> {
+        (0..DIMENSIONS.get_num_nodes())
+            .map(|node_id| {
+                let node_status = match node_id {
+                    0 => NodeStatus::Start,
+                    6 => NodeStatus::End,
+                    _ if walls.contains(&node_id) => NodeStatus::Off,
+                    _ => NodeStatus::On,
+                };
+                GridNode::build_preview(node_status, DEFAULT_WEIGHT)
+            })
+            .collect()
+    }
+
+    fn get_replan_count(run: &FogRun) -> usize {
+        run.steps.iter().filter(|step| step.is_replan).count()
+    }
+
+    fn assert_walk_is_connected(nodes: &[GridNode], run: &FogRun) {
+        run.steps.windows(2).for_each(|pair| {
+            assert!(DIMENSIONS.get_neighbors(pair[0].position).any(|neighbor| neighbor == pair[1].position));
+            assert_ne!(nodes[pair[1].position].node_status, NodeStatus::Off);
+        });
+    }
+
+    #[test]
+    fn open_boards_are_crossed_optimally_without_replanning() {
+        let nodes = get_nodes(&[]);
+        let run = explore(&nodes, DIMENSIONS, 0, 6, 1);
+
+        assert!(run.is_goal_reached);
+        assert_eq!(get_replan_count(&run), 0);
+        assert_eq!(Some(run.steps.last().unwrap().travelled_cost), run.optimal_cost);
+        assert_eq!(run.steps[0].revealed.len(), 4);
+    }
+
+    #[test]
+    fn hidden_walls_force_detours_and_replans() {
+        // a wall hangs down from the top row between start and end, open only at the bottom
+        let walls = [3, 10, 17, 24, 31, 38];
+        let nodes = get_nodes(&walls);
+        let run = explore(&nodes, DIMENSIONS, 0, 6, 1);
+
+        assert!(run.is_goal_reached);
+        assert!(get_replan_count(&run) > 0);
+        assert_walk_is_connected(&nodes, &run);
+        assert!(Some(run.steps.last().unwrap().travelled_cost) >= run.optimal_cost);
+
+        // seeing the whole board up front makes the first plan the right one
+        let omniscient = explore(&nodes, DIMENSIONS, 0, 6, 10);
+        assert_eq!(get_replan_count(&omniscient), 0);
+        assert_eq!(Some(omniscient.steps.last().unwrap().travelled_cost), omniscient.optimal_cost);
+    }
+
+    #[test]
+    fn blind_agents_bump_into_walls_instead_of_walking_through_them() {
+        let walls = [3, 10, 17, 24, 31, 38];
+        let nodes = get_nodes(&walls);
+        let run = explore(&nodes, DIMENSIONS, 0, 6, 0);
+
+        assert!(run.is_goal_reached);
+        assert!(get_replan_count(&run) > 0);
+        assert_walk_is_connected(&nodes, &run);
+        assert_eq!(Some(run.steps.last().unwrap().travelled_cost), Some(run.steps.len() - 1));
+        assert!(Some(run.steps.last().unwrap().travelled_cost) >= run.optimal_cost);
+    }
+
+    #[test]
+    fn jumps_through_a_portal_once_it_is_in_sight() {
+        let mut nodes = get_nodes(&[]);
+        nodes[1].portal = Some(Portal { partner: 5, cost: 1 });
+        nodes[5].portal = Some(Portal { partner: 1, cost: 1 });
+        let run = explore(&nodes, DIMENSIONS, 0, 6, 1);
+
+        assert!(run.is_goal_reached);
+        assert_eq!(run.optimal_cost, Some(3));
+        assert_eq!(run.steps.iter().map(|step| step.position).collect::<Vec<_>>(), vec![0, 1, 5, 6]);
+        assert_eq!(run.steps.last().unwrap().travelled_cost, 3);
+    }
+
+    #[test]
+    fn gives_up_once_every_route_is_known_to_be_blocked() {
+        let nodes = get_nodes(&[5, 12, 13]);
+        let run = explore(&nodes, DIMENSIONS, 0, 6, 2);
+
+        assert!(!run.is_goal_reached);
+        assert_eq!(run.optimal_cost, None);
+        assert!(run.steps.last().unwrap().plan.is_empty());
+        assert_walk_is_connected(&nodes, &run);
+    }
+}
@@ -0,0 +1,122 @@
+use web_sys::HtmlElement;
+
+use crate::board::{Direction, GridDimensions, Portal};
+use crate::dijkstras::ShortestPathTree;
+
+pub const HEAT_CELL_CLASS: &str =
+    "flex items-center justify-center overflow-hidden leading-none text-[0.5rem] text-gray-900";
+
+const NEAR_HUE: f64 = 55.0;
+const FAR_HUE: f64 = 275.0;
+/// Marks a cell whose best way in is the jump from its portal partner.
+const PORTAL_MARKER: &str = "◎";
+
+#[derive(Clone, Copy, Default)]
+pub struct HeatmapOptions {
+    pub show_distances: bool,
+    pub show_arrows: bool,
+}
+
+pub fn get_heat_style(distance: usize, max_distance: usize) -> String {
+    let ratio = match max_distance {
+        0 => 0.0,
+        _ => distance as f64 / max_distance as f64,
+    };
+    let hue = NEAR_HUE + (FAR_HUE - NEAR_HUE) * ratio;
+
+    format!("background-color: hsl({:.0}, 75%, 55%);", hue)
+}
+
+pub fn get_predecessor_arrow(
+    node_id: usize,
+    previous_node_id: usize,
+    portal: Option<Portal>,
+    dimensions: GridDimensions,
+) -> &'static str {
+    if portal.is_some_and(|portal| portal.partner == previous_node_id) {
+        return PORTAL_MARKER;
+    }
+
+    Direction::between(node_id, previous_node_id, dimensions).map_or("", |direction| direction.get_arrow())
+}
+
+pub fn get_heat_label(
+    tree: &ShortestPathTree,
+    node_id: usize,
+    portal: Option<Portal>,
+    dimensions: GridDimensions,
+    options: HeatmapOptions,
+) -> String {
+    let mut label = String::new();
+
+    if options.show_distances {
+        label.push_str(&tree.distances[node_id].to_string());
+    }
+
+    if options.show_arrows {
+        if let Some(previous_node_id) = tree.previous[node_id] {
+            label.push_str(get_predecessor_arrow(node_id, previous_node_id, portal, dimensions));
+        }
+    }
+
+    label
+}
+
+pub fn set_heat_square(node: &HtmlElement, style: &str, label: &str) {
+    node.set_class_name(HEAT_CELL_CLASS);
+    let _ = node.set_attribute("style", style);
+    node.set_inner_text(label);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heat_style_spans_gradient_from_near_to_far() {
+        assert_eq!(get_heat_style(0, 10), "background-color: hsl(55, 75%, 55%);");
+        assert_eq!(get_heat_style(10, 10), "background-color: hsl(275, 75%, 55%);");
+        assert_eq!(get_heat_style(0, 0), "background-color: hsl(55, 75%, 55%);");
+    }
+
+    #[test]
+    fn predecessor_arrow_points_towards_previous_node() {
+        let dimensions = GridDimensions::square(10);
+
+        assert_eq!(get_predecessor_arrow(11, 10, None, dimensions), "←");
+        assert_eq!(get_predecessor_arrow(11, 12, None, dimensions), "→");
+        assert_eq!(get_predecessor_arrow(11, 1, None, dimensions), "↑");
+        assert_eq!(get_predecessor_arrow(11, 21, None, dimensions), "↓");
+        // one apart in id, but at opposite ends of two rows
+        assert_eq!(get_predecessor_arrow(10, 9, None, dimensions), "");
+    }
+
+    #[test]
+    fn predecessor_through_a_portal_gets_the_portal_marker() {
+        let dimensions = GridDimensions::square(10);
+        let portal = |partner: usize| Some(Portal { partner, cost: 1 });
+
+        assert_eq!(get_predecessor_arrow(55, 2, portal(2), dimensions), PORTAL_MARKER);
+        // a partner that is also a neighbor is still the jump
+        assert_eq!(get_predecessor_arrow(11, 12, portal(12), dimensions), PORTAL_MARKER);
+        assert_eq!(get_predecessor_arrow(11, 12, portal(55), dimensions), "→");
+    }
+
+    #[test]
+    fn heat_label_respects_options() {
+        let tree = ShortestPathTree {
+            distances: vec![0, 1, 2],
+            previous: vec![None, Some(0), Some(1)],
+            settled_ordered: vec![0, 1, 2],
+        };
+        let both = HeatmapOptions { show_distances: true, show_arrows: true };
+        let arrows_only = HeatmapOptions { show_distances: false, show_arrows: true };
+
+        let dimensions = GridDimensions::square(3);
+
+        assert_eq!(get_heat_label(&tree, 2, None, dimensions, both), "2←");
+        assert_eq!(get_heat_label(&tree, 2, None, dimensions, arrows_only), "←");
+        assert_eq!(get_heat_label(&tree, 0, None, dimensions, both), "0");
+        assert_eq!(get_heat_label(&tree, 2, None, dimensions, HeatmapOptions::default()), "");
+    }
+}
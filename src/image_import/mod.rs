@@ -0,0 +1,243 @@
+use std::fmt;
+use std::ops::Range;
+
+use png::{ColorType, Decoder, Transformations};
+
+use crate::{DEFAULT_WEIGHT, MAX_WEIGHT};
+
+/// Cells darker than this (0-255 luminance) become walls.
+const WALL_LUMINANCE: f64 = 64.0;
+const MARKER_MIN_CHANNEL: f64 = 150.0;
+const MARKER_MAX_OTHER_CHANNELS: f64 = 100.0;
+
+#[derive(Debug)]
+pub enum ImportError {
+    Decode(png::DecodingError),
+    EmptyImage,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::Decode(error) => write!(f, "could not decode the PNG: {}", error),
+            ImportError::EmptyImage => write!(f, "the image has no pixels"),
+        }
+    }
+}
+
+impl From<png::DecodingError> for ImportError {
+    fn from(error: png::DecodingError) -> Self {
+        ImportError::Decode(error)
+    }
+}
+
+/// Decoded pixels as straight RGBA, row by row.
+pub struct RgbaImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 4]>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedMap {
+    pub walls: Vec<bool>,
+    pub weights: Vec<usize>,
+    pub start_node_id: Option<usize>,
+    pub end_node_id: Option<usize>,
+}
+
+pub fn decode_png(bytes: &[u8]) -> Result<RgbaImage, ImportError> {
+    let mut decoder = Decoder::new(bytes);
+    // palettes, low bit depths and 16-bit channels all come out as plain 8-bit samples
+    decoder.set_transformations(Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer)?;
+
+    let (width, height) = (frame.width as usize, frame.height as usize);
+    if width == 0 || height == 0 {
+        return Err(ImportError::EmptyImage);
+    }
+
+    let samples = frame.color_type.samples();
+    let pixels = buffer[..frame.buffer_size()]
+        .chunks(frame.line_size)
+        .flat_map(|line| line[..width * samples].chunks(samples))
+        .map(|sample| match frame.color_type {
+            ColorType::Grayscale => [sample[0], sample[0], sample[0], 255],
+            ColorType::GrayscaleAlpha => [sample[0], sample[0], sample[0], sample[1]],
+            ColorType::Rgb => [sample[0], sample[1], sample[2], 255],
+            _ => [sample[0], sample[1], sample[2], sample[3]],
+        })
+        .collect();
+
+    Ok(RgbaImage { width, height, pixels })
+}
+
+/// The pixels covered by cell `index` of `count`, at least one even when the image is smaller than the board.
+fn get_pixel_range(index: usize, count: usize, size: usize) -> Range<usize> {
+    let start = index * size / count;
+
+    start..((index + 1) * size / count).max(start + 1)
+}
+
+/// Averages the block of pixels under each cell, with transparency showing white (open ground).
+fn get_cell_color(image: &RgbaImage, column: usize, row: usize, columns: usize, rows: usize) -> [f64; 3] {
+    let x_range = get_pixel_range(column, columns, image.width);
+    let y_range = get_pixel_range(row, rows, image.height);
+    let mut color = [0.0; 3];
+    let mut count = 0.0;
+
+    for y in y_range.clone() {
+        for x in x_range.clone() {
+            let [r, g, b, a] = image.pixels[y * image.width + x];
+            let alpha = a as f64 / 255.0;
+            [r, g, b].iter().enumerate().for_each(|(channel, &value)| {
+                color[channel] += value as f64 * alpha + 255.0 * (1.0 - alpha);
+            });
+            count += 1.0;
+        }
+    }
+
+    color.map(|channel| channel / count)
+}
+
+fn get_luminance([r, g, b]: [f64; 3]) -> f64 {
+    0.299 * r + 0.587 * g + 0.114 * b
+}
+
+/// How strongly `channel` dominates the cell, or `None` if the cell is not a marker of that color.
+fn get_marker_strength(color: [f64; 3], channel: usize) -> Option<f64> {
+    let strongest_other = (0..3)
+        .filter(|&other| other != channel)
+        .map(|other| color[other])
+        .fold(0.0, f64::max);
+
+    match color[channel] >= MARKER_MIN_CHANNEL && strongest_other <= MARKER_MAX_OTHER_CHANNELS {
+        true => Some(color[channel] - strongest_other),
+        false => None,
+    }
+}
+
+fn get_weight(luminance: f64) -> usize {
+    let darkness = ((255.0 - luminance) / (255.0 - WALL_LUMINANCE)).clamp(0.0, 1.0);
+
+    DEFAULT_WEIGHT + (darkness * (MAX_WEIGHT - DEFAULT_WEIGHT) as f64).round() as usize
+}
+
+/// Downsamples the image onto a `columns` x `rows` board: white is open, grays cost more the darker
+/// they get and near-black is a wall. With `use_markers`, the reddest cell becomes the end and the
+/// greenest the start.
+pub fn map_image(image: &RgbaImage, columns: usize, rows: usize, use_markers: bool) -> ImportedMap {
+    let colors: Vec<[f64; 3]> = (0..columns * rows)
+        .map(|node_id| get_cell_color(image, node_id % columns, node_id / columns, columns, rows))
+        .collect();
+
+    let find_marker = |channel: usize| {
+        colors
+            .iter()
+            .enumerate()
+            .filter_map(|(node_id, &color)| get_marker_strength(color, channel).map(|strength| (node_id, strength)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(node_id, _)| node_id)
+    };
+    let (start_node_id, end_node_id) = match use_markers {
+        true => (find_marker(1), find_marker(0)),
+        false => (None, None),
+    };
+    let is_marker = |node_id: usize| Some(node_id) == start_node_id || Some(node_id) == end_node_id;
+
+    let walls = colors
+        .iter()
+        .enumerate()
+        .map(|(node_id, &color)| !is_marker(node_id) && get_luminance(color) < WALL_LUMINANCE)
+        .collect();
+    let weights = colors
+        .iter()
+        .enumerate()
+        .map(|(node_id, &color)| match is_marker(node_id) {
+            true => DEFAULT_WEIGHT,
+            false => get_weight(get_luminance(color)),
+        })
+        .collect();
+
+    ImportedMap {
+        walls,
+        weights,
+        start_node_id,
+        end_node_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+    const GRAY: [u8; 4] = [150, 150, 150, 255];
+    const RED: [u8; 4] = [230, 20, 20, 255];
+    const GREEN: [u8; 4] = [20, 200, 20, 255];
+
+    fn encode_rgba(width: u32, height: u32, pixels: &[[u8; 4]]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, width, height);
+            encoder.set_color(ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(&pixels.concat()).unwrap();
+        }
+        bytes
+    }
+
+    #[test]
+    fn decodes_rgba_png() {
+        let image = decode_png(&encode_rgba(2, 1, &[RED, GRAY])).unwrap();
+
+        assert_eq!((image.width, image.height), (2, 1));
+        assert_eq!(image.pixels, vec![RED, GRAY]);
+        assert!(decode_png(b"not a png").is_err());
+    }
+
+    #[test]
+    fn maps_dark_pixels_to_walls_and_grays_to_weights() {
+        let image = RgbaImage { width: 3, height: 1, pixels: vec![WHITE, GRAY, BLACK] };
+        let map = map_image(&image, 3, 1, false);
+
+        assert_eq!(map.walls, vec![false, false, true]);
+        assert_eq!(map.weights[0], DEFAULT_WEIGHT);
+        assert!(map.weights[1] > DEFAULT_WEIGHT && map.weights[1] < MAX_WEIGHT);
+        assert_eq!(map.weights[2], MAX_WEIGHT);
+    }
+
+    #[test]
+    fn downsamples_blocks_and_finds_markers() {
+        // 4x4 image onto a 2x2 board: each cell averages a 2x2 block
+        #[rustfmt::skip]
+        let pixels = vec![
+            GREEN, GREEN, WHITE, WHITE,
+            GREEN, GREEN, WHITE, WHITE,
+            BLACK, BLACK, RED,   RED,
+            BLACK, BLACK, RED,   RED,
+        ];
+        let image = RgbaImage { width: 4, height: 4, pixels };
+
+        let map = map_image(&image, 2, 2, true);
+        assert_eq!(map.start_node_id, Some(0));
+        assert_eq!(map.end_node_id, Some(3));
+        assert_eq!(map.walls, vec![false, false, true, false]);
+        assert_eq!(map.weights[0], DEFAULT_WEIGHT);
+
+        let without_markers = map_image(&image, 2, 2, false);
+        assert_eq!(without_markers.start_node_id, None);
+        assert_eq!(without_markers.end_node_id, None);
+    }
+
+    #[test]
+    fn upsamples_small_images() {
+        let image = RgbaImage { width: 1, height: 1, pixels: vec![BLACK] };
+
+        assert!(map_image(&image, 3, 3, false).walls.iter().all(|&is_wall| is_wall));
+    }
+}
@@ -1,21 +1,42 @@
 mod adjacency_list;
+pub mod benchmark;
 mod board;
 mod dijkstras;
+mod drawing;
+mod export;
+mod fog;
+mod heatmap;
+mod image_import;
+mod lpa_star;
+mod movingai;
+mod obstacles;
+mod recording;
+mod rng;
+mod terrain;
 #[cfg(test)]
 mod tests_common;
 mod utils;
 mod visualizer;
 
-use board::Board;
+use board::{Board, GridDimensions};
 
 
-const GRID_SIZE: usize = 2500;
+const DEFAULT_DIMENSIONS: GridDimensions = GridDimensions::square(50);
+const DEFAULT_WEIGHT: usize = 1;
+const MAX_WEIGHT: usize = 9;
 const DEFAULT_COLOR: &str = "bg-gray-700";
 const START_COLOR: &str = "bg-green-700";
 const END_COLOR: &str = "bg-red-700";
 const OFF_COLOR: &str = "bg-gray-900";
 const VISITED_COLOR: &str = "bg-blue-800";
+const FRONTIER_COLOR: &str = "bg-cyan-500";
 const PATH_COLOR: &str = "bg-yellow-600";
+const TRACE_COLOR: &str = "ring-fuchsia-400";
+const UNKNOWN_COLOR: &str = "bg-zinc-800";
+const DISCOVERED_COLOR: &str = "bg-orange-600";
+const AGENT_COLOR: &str = "bg-fuchsia-500";
+// open cells get lighter the more they cost to enter
+const WEIGHT_COLORS: [&str; 4] = ["bg-stone-600", "bg-stone-500", "bg-stone-400", "bg-stone-300"];
 
 #[derive(Clone, Debug, PartialEq)]
 enum NodeStatus {
@@ -25,6 +46,12 @@ enum NodeStatus {
     End,
     Path,
     Visited,
+    Frontier,
+    /// Not yet seen by an agent exploring in the fog.
+    Unknown,
+    /// A wall an exploring agent only found on its way.
+    Discovered,
+    Agent,
 }
 
 pub fn run() {
@@ -0,0 +1,396 @@
+use std::collections::BTreeSet;
+
+use crate::adjacency_list;
+use crate::board::{Direction, GridDimensions, GridNode, Portal};
+use crate::dijkstras::{Heuristic, ShortestPathTree};
+use crate::NodeStatus;
+
+const INFINITY: usize = usize::MAX;
+
+/// Queue priority of a cell: the estimated total cost first, then the cost so far to break ties.
+type Key = (usize, usize);
+
+/// Lifelong Planning A* between a fixed start and goal. When cells change it repairs only the part
+/// of its previous search the change reaches, instead of searching the whole board again.
+pub struct LpaStar {
+    dimensions: GridDimensions,
+    start_node_id: usize,
+    goal_node_id: usize,
+    /// The cost of entering each cell, `None` for walls.
+    costs: Vec<Option<usize>>,
+    directions: Vec<Option<Direction>>,
+    portals: Vec<Option<Portal>>,
+    heuristic: Heuristic,
+    /// Cost from the start as of the last expansion.
+    g: Vec<usize>,
+    /// One-step lookahead of `g` from the neighbors; cells where the two differ are queued.
+    rhs: Vec<usize>,
+    queue: BTreeSet<(Key, usize)>,
+    queued_keys: Vec<Option<Key>>,
+}
+
+/// What a replan did, and once measured, what searching from scratch would have taken.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReplanReport {
+    pub changed_cells: usize,
+    pub expanded: Vec<usize>,
+    /// Only filled in by `measure_full_replan`, since it takes the very search the replan avoided.
+    pub full_replan_expansions: Option<usize>,
+    pub cost: Option<usize>,
+}
+
+impl ReplanReport {
+    pub fn get_saved_expansions(&self) -> Option<usize> {
+        self.full_replan_expansions.map(|full| full.saturating_sub(self.expanded.len()))
+    }
+
+    /// Searches the board the planner was brought up to date with from scratch, to see what the replan saved.
+    pub fn measure_full_replan(&mut self, lpa_star: &LpaStar, nodes: &[GridNode]) {
+        let expansions = LpaStar::new(nodes, lpa_star.dimensions, lpa_star.start_node_id, lpa_star.goal_node_id)
+            .compute_shortest_path()
+            .len();
+        self.full_replan_expansions = Some(expansions);
+    }
+}
+
+fn get_entry_cost(grid_node: &GridNode) -> Option<usize> {
+    match grid_node.node_status {
+        NodeStatus::Off => None,
+        _ => Some(grid_node.weight),
+    }
+}
+
+impl LpaStar {
+    pub fn new(nodes: &[GridNode], dimensions: GridDimensions, start_node_id: usize, goal_node_id: usize) -> Self {
+        let num_nodes = nodes.len();
+        let mut lpa_star = LpaStar {
+            dimensions,
+            start_node_id,
+            goal_node_id,
+            costs: nodes.iter().map(get_entry_cost).collect(),
+            directions: nodes.iter().map(|grid_node| grid_node.direction).collect(),
+            portals: nodes.iter().map(|grid_node| grid_node.portal).collect(),
+            heuristic: Heuristic::new(nodes, dimensions, goal_node_id),
+            g: vec![INFINITY; num_nodes],
+            rhs: vec![INFINITY; num_nodes],
+            queue: BTreeSet::new(),
+            queued_keys: vec![None; num_nodes],
+        };
+
+        lpa_star.rhs[start_node_id] = 0;
+        lpa_star.enqueue(start_node_id);
+        lpa_star
+    }
+
+    /// Whether this planner's search can be reused for a board of this size between these cells.
+    pub fn is_planning(&self, dimensions: GridDimensions, start_node_id: usize, goal_node_id: usize) -> bool {
+        self.dimensions == dimensions && self.start_node_id == start_node_id && self.goal_node_id == goal_node_id
+    }
+
+    fn calculate_key(&self, node_id: usize) -> Key {
+        let cost_so_far = self.g[node_id].min(self.rhs[node_id]);
+        let heuristic = self.heuristic.get(node_id);
+
+        (cost_so_far.saturating_add(heuristic), cost_so_far)
+    }
+
+    fn enqueue(&mut self, node_id: usize) {
+        let key = self.calculate_key(node_id);
+        self.queue.insert((key, node_id));
+        self.queued_keys[node_id] = Some(key);
+    }
+
+    fn dequeue(&mut self, node_id: usize) {
+        if let Some(key) = self.queued_keys[node_id].take() {
+            self.queue.remove(&(key, node_id));
+        }
+    }
+
+    /// Whether one step from `from` to its neighbor `to` goes along every one-way arrow involved.
+    fn is_move_allowed(&self, from: usize, to: usize) -> bool {
+        Direction::between(from, to, self.dimensions)
+            .is_some_and(|direction| adjacency_list::is_move_allowed(self.directions[from], self.directions[to], direction))
+    }
+
+    /// The cells with an edge into this one, each with that edge's cost: the neighbors it can be
+    /// entered from and its portal partner.
+    fn get_predecessors(&self, node_id: usize) -> Vec<(usize, usize)> {
+        let Some(cost) = self.costs[node_id] else {
+            return Vec::new();
+        };
+
+        self.dimensions
+            .get_neighbors(node_id)
+            .filter(|&neighbor| self.is_move_allowed(neighbor, node_id))
+            .map(|neighbor| (neighbor, cost))
+            .chain(self.portals[node_id].map(|portal| (portal.partner, portal.cost)))
+            .collect()
+    }
+
+    /// The cells whose lookahead depends on this one; a superset is fine, since each is simply redone.
+    fn get_successors(&self, node_id: usize) -> Vec<usize> {
+        self.dimensions
+            .get_neighbors(node_id)
+            .chain(self.portals[node_id].map(|portal| portal.partner))
+            .collect()
+    }
+
+    fn update_node(&mut self, node_id: usize) {
+        if node_id != self.start_node_id {
+            self.rhs[node_id] = self
+                .get_predecessors(node_id)
+                .into_iter()
+                .map(|(predecessor, cost)| self.g[predecessor].saturating_add(cost))
+                .min()
+                .unwrap_or(INFINITY);
+        }
+
+        self.dequeue(node_id);
+        if self.g[node_id] != self.rhs[node_id] {
+            self.enqueue(node_id);
+        }
+    }
+
+    /// Expands cells until the goal's cost is settled; returns them in the order they were expanded.
+    pub fn compute_shortest_path(&mut self) -> Vec<usize> {
+        let mut expanded = Vec::new();
+
+        while let Some(&(key, node_id)) = self.queue.first() {
+            let is_goal_settled = self.g[self.goal_node_id] == self.rhs[self.goal_node_id];
+            if key >= self.calculate_key(self.goal_node_id) && is_goal_settled {
+                break;
+            }
+
+            self.dequeue(node_id);
+            expanded.push(node_id);
+
+            if self.g[node_id] > self.rhs[node_id] {
+                self.g[node_id] = self.rhs[node_id];
+            } else {
+                // the cell got more expensive, so everything routed through it has to be checked again
+                self.g[node_id] = INFINITY;
+                self.update_node(node_id);
+            }
+
+            self.get_successors(node_id).into_iter().for_each(|successor| self.update_node(successor));
+        }
+
+        expanded
+    }
+
+    /// Picks up every cell whose cost, arrow or portal changed on the board since the last call and returns
+    /// how many did. A new cost only changes the price of entering that cell, so only its lookahead has to
+    /// be redone; a new arrow also changes which moves out of it are allowed, so its neighbors' are redone
+    /// too. Portals change at both ends at once, so each end redoes its own.
+    pub fn update_costs(&mut self, nodes: &[GridNode]) -> usize {
+        let changed_node_ids: Vec<usize> = nodes
+            .iter()
+            .enumerate()
+            .filter(|&(node_id, grid_node)| {
+                get_entry_cost(grid_node) != self.costs[node_id]
+                    || grid_node.direction != self.directions[node_id]
+                    || grid_node.portal != self.portals[node_id]
+            })
+            .map(|(node_id, _)| node_id)
+            .collect();
+
+        // portals shape the estimate, so every queued key is worked out again with the new one
+        if changed_node_ids.iter().any(|&node_id| nodes[node_id].portal != self.portals[node_id]) {
+            self.portals = nodes.iter().map(|grid_node| grid_node.portal).collect();
+            self.heuristic = Heuristic::new(nodes, self.dimensions, self.goal_node_id);
+            let queued_node_ids: Vec<usize> = self.queue.iter().map(|&(_, node_id)| node_id).collect();
+            queued_node_ids.into_iter().for_each(|node_id| {
+                self.dequeue(node_id);
+                self.enqueue(node_id);
+            });
+        }
+
+        changed_node_ids.iter().for_each(|&node_id| {
+            let is_turned = nodes[node_id].direction != self.directions[node_id];
+            self.costs[node_id] = get_entry_cost(&nodes[node_id]);
+            self.directions[node_id] = nodes[node_id].direction;
+            self.update_node(node_id);
+
+            if is_turned {
+                let neighbors: Vec<usize> = self.dimensions.get_neighbors(node_id).collect();
+                neighbors.into_iter().for_each(|neighbor| self.update_node(neighbor));
+            }
+        });
+
+        changed_node_ids.len()
+    }
+
+    pub fn get_cost(&self) -> Option<usize> {
+        Some(self.g[self.goal_node_id]).filter(|&cost| cost != INFINITY)
+    }
+
+    fn get_best_predecessor(&self, node_id: usize) -> Option<usize> {
+        self.get_predecessors(node_id)
+            .into_iter()
+            .filter(|&(predecessor, _)| self.g[predecessor] != INFINITY)
+            .min_by_key(|&(predecessor, cost)| self.g[predecessor] + cost)
+            .map(|(predecessor, _)| predecessor)
+    }
+
+    /// The cells between start and goal, both left out, like `DijkstrasTraversal::get_path`.
+    pub fn get_path(&self) -> Vec<usize> {
+        if self.get_cost().is_none() {
+            return Vec::new();
+        }
+
+        let mut path = Vec::new();
+        let mut current_node_id = self.goal_node_id;
+        while let Some(previous_node_id) = self.get_best_predecessor(current_node_id) {
+            if previous_node_id == self.start_node_id || path.len() >= self.g.len() {
+                break;
+            }
+            path.push(previous_node_id);
+            current_node_id = previous_node_id;
+        }
+
+        path.reverse();
+        path
+    }
+
+    pub fn get_shortest_path_tree(&self) -> ShortestPathTree {
+        let previous = (0..self.g.len())
+            .map(|node_id| match node_id == self.start_node_id || self.g[node_id] == INFINITY {
+                true => None,
+                false => self.get_best_predecessor(node_id),
+            })
+            .collect();
+
+        ShortestPathTree {
+            distances: self.g.clone(),
+            previous,
+            settled_ordered: (0..self.g.len()).filter(|&node_id| self.g[node_id] != INFINITY).collect(),
+        }
+    }
+}
+
+/// Brings the planner up to date with the board, expanding only the cells the changes reach.
+pub fn replan(lpa_star: &mut LpaStar, nodes: &[GridNode]) -> ReplanReport {
+    let changed_cells = lpa_star.update_costs(nodes);
+    let expanded = lpa_star.compute_shortest_path();
+
+    ReplanReport {
+        changed_cells,
+        expanded,
+        full_replan_expansions: None,
+        cost: lpa_star.get_cost(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstras::DijkstrasTraversal;
+    use crate::tests_common::{get_mock_grid, get_mock_nodes, MOCK_DIMENSIONS};
+
+    fn get_solved_planner(nodes: &[GridNode]) -> LpaStar {
+        let mut lpa_star = LpaStar::new(nodes, MOCK_DIMENSIONS, 0, 99);
+        lpa_star.compute_shortest_path();
+        lpa_star
+    }
+
+    fn assert_path_is_walkable(nodes: &[GridNode], path: &[usize]) {
+        let walk: Vec<usize> = [0].into_iter().chain(path.iter().copied()).chain([99]).collect();
+        walk.windows(2).for_each(|pair| assert!(MOCK_DIMENSIONS.get_neighbors(pair[0]).any(|neighbor| neighbor == pair[1])));
+        path.iter().for_each(|&node_id| assert_ne!(nodes[node_id].node_status, NodeStatus::Off));
+    }
+
+    #[test]
+    fn matches_dijkstra_from_scratch() {
+        let nodes = get_mock_nodes(get_mock_grid());
+        let lpa_star = get_solved_planner(&nodes);
+        let dijkstras_traversal = DijkstrasTraversal::run(&nodes, MOCK_DIMENSIONS, 0, 99);
+
+        assert_eq!(lpa_star.get_cost(), dijkstras_traversal.get_cost());
+        assert_eq!(lpa_star.get_path().len(), dijkstras_traversal.get_path().len());
+        assert_path_is_walkable(&nodes, &lpa_star.get_path());
+    }
+
+    #[test]
+    fn repairs_the_search_after_a_wall_lands_on_the_path() {
+        let mut nodes = get_mock_nodes(get_mock_grid());
+        let mut lpa_star = get_solved_planner(&nodes);
+        let blocked_node_id = lpa_star.get_path()[lpa_star.get_path().len() / 2];
+
+        nodes[blocked_node_id].node_status = NodeStatus::Off;
+        let mut report = replan(&mut lpa_star, &nodes);
+        assert_eq!(report.full_replan_expansions, None);
+        report.measure_full_replan(&lpa_star, &nodes);
+
+        assert_eq!(report.changed_cells, 1);
+        assert_eq!(report.cost, DijkstrasTraversal::run(&nodes, MOCK_DIMENSIONS, 0, 99).get_cost());
+        assert!(!lpa_star.get_path().contains(&blocked_node_id));
+        assert_path_is_walkable(&nodes, &lpa_star.get_path());
+        assert!(report.expanded.len() < report.full_replan_expansions.unwrap());
+    }
+
+    #[test]
+    fn weight_and_wall_changes_off_the_path_cost_little() {
+        let mut nodes = get_mock_nodes(get_mock_grid());
+        let mut lpa_star = get_solved_planner(&nodes);
+
+        nodes[9].weight = 5;
+        nodes[2].node_status = NodeStatus::Off;
+        let mut report = replan(&mut lpa_star, &nodes);
+        report.measure_full_replan(&lpa_star, &nodes);
+
+        assert_eq!(report.changed_cells, 2);
+        assert_eq!(report.cost, DijkstrasTraversal::run(&nodes, MOCK_DIMENSIONS, 0, 99).get_cost());
+        assert!(report.get_saved_expansions().unwrap() > 0);
+    }
+
+    #[test]
+    fn follows_one_way_cells_turned_after_the_first_search() {
+        let mut nodes = get_mock_nodes(get_mock_grid());
+        let mut lpa_star = get_solved_planner(&nodes);
+        let turned_node_id = lpa_star.get_path()[lpa_star.get_path().len() / 2];
+
+        nodes[turned_node_id].direction = Some(Direction::Up);
+        let report = replan(&mut lpa_star, &nodes);
+
+        assert_eq!(report.changed_cells, 1);
+        assert_eq!(report.cost, DijkstrasTraversal::run(&nodes, MOCK_DIMENSIONS, 0, 99).get_cost());
+        // every step of the new path goes along the arrows it touches
+        let walk: Vec<usize> = [0].into_iter().chain(lpa_star.get_path()).chain([99]).collect();
+        walk.windows(2).for_each(|pair| assert!(lpa_star.is_move_allowed(pair[0], pair[1])));
+    }
+
+    #[test]
+    fn takes_and_drops_a_portal_linked_after_the_first_search() {
+        let mut nodes = get_mock_nodes(get_mock_grid());
+        let mut lpa_star = get_solved_planner(&nodes);
+        let cost = lpa_star.get_cost();
+
+        nodes[2].portal = Some(Portal { partner: 97, cost: 1 });
+        nodes[97].portal = Some(Portal { partner: 2, cost: 1 });
+        let report = replan(&mut lpa_star, &nodes);
+
+        assert_eq!(report.changed_cells, 2);
+        assert_eq!(report.cost, DijkstrasTraversal::run(&nodes, MOCK_DIMENSIONS, 0, 99).get_cost());
+        assert!(report.cost < cost);
+        let path = lpa_star.get_path();
+        assert!(path.windows(2).any(|pair| pair == [2, 97]));
+
+        nodes[2].portal = None;
+        nodes[97].portal = None;
+        assert_eq!(replan(&mut lpa_star, &nodes).cost, cost);
+    }
+
+    #[test]
+    fn reports_no_path_once_the_goal_is_sealed_and_finds_it_again() {
+        let mut nodes = get_mock_nodes(get_mock_grid());
+        let mut lpa_star = get_solved_planner(&nodes);
+        let cost = lpa_star.get_cost();
+
+        [89, 98].iter().for_each(|&node_id| nodes[node_id].node_status = NodeStatus::Off);
+        assert_eq!(replan(&mut lpa_star, &nodes).cost, None);
+        assert!(lpa_star.get_path().is_empty());
+
+        [89, 98].iter().for_each(|&node_id| nodes[node_id].node_status = NodeStatus::On);
+        assert_eq!(replan(&mut lpa_star, &nodes).cost, cost);
+    }
+}
@@ -0,0 +1,382 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::f64::consts::SQRT_2;
+use std::fmt;
+
+use crate::board::{GridDimensions, GridNode};
+use crate::dijkstras::Algorithm;
+use crate::NodeStatus;
+
+/// Scenario lengths are printed with a handful of decimals, so compare with some slack.
+const LENGTH_TOLERANCE: f64 = 1e-4;
+
+/// The benchmark's moves as `(x, y)` offsets: the four straight ones, then the diagonals.
+const OCTILE_MOVES: [(isize, isize); 8] = [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (1, -1), (-1, 1), (1, 1)];
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+fn parse_error(line: usize, message: impl Into<String>) -> ParseError {
+    ParseError { line, message: message.into() }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MovingAiMap {
+    pub dimensions: GridDimensions,
+    pub walls: Vec<bool>,
+}
+
+/// One line of a `.scen` file; coordinates are `(x, y)` from the top left.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scenario {
+    pub bucket: usize,
+    pub map_width: usize,
+    pub map_height: usize,
+    pub start: (usize, usize),
+    pub goal: (usize, usize),
+    pub optimal_length: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScenarioMismatch {
+    /// Position of the scenario in the file, counting from 0.
+    pub index: usize,
+    pub scenario: Scenario,
+    pub found_length: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScenarioReport {
+    pub checked: usize,
+    /// Scenarios written for a map of a different size than the loaded one.
+    pub skipped: usize,
+    /// Scenarios whose start or goal is a wall on the board, which have no path to check.
+    pub blocked: usize,
+    pub mismatches: Vec<ScenarioMismatch>,
+}
+
+/// Ground (`.` and `G`) and swamp (`S`) are open; trees, water and out-of-bounds cells are walls.
+/// Swamp costs the same as ground: the benchmark measures its optimal lengths with every open tile
+/// at one, so a heavier swamp would fail every scenario that wades through one.
+fn is_passable(tile: char) -> bool {
+    matches!(tile, '.' | 'G' | 'S')
+}
+
+fn parse_header_value(line_number: usize, line: Option<&str>, key: &str) -> Result<usize, ParseError> {
+    let line = line.ok_or_else(|| parse_error(line_number, format!("expected \"{key}\", found the end of the file")))?;
+
+    match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [found_key, value] if *found_key == key => value
+            .parse()
+            .map_err(|_| parse_error(line_number, format!("\"{value}\" is not a valid {key}"))),
+        _ => Err(parse_error(line_number, format!("expected \"{key} <number>\", found \"{line}\""))),
+    }
+}
+
+pub fn parse_map(text: &str) -> Result<MovingAiMap, ParseError> {
+    let mut lines = text.lines().map(str::trim_end);
+
+    match lines.next() {
+        Some(line) if line.starts_with("type") => {}
+        line => return Err(parse_error(1, format!("expected \"type octile\", found \"{}\"", line.unwrap_or_default()))),
+    }
+    let rows = parse_header_value(2, lines.next(), "height")?;
+    let columns = parse_header_value(3, lines.next(), "width")?;
+    if lines.next() != Some("map") {
+        return Err(parse_error(4, "expected \"map\""));
+    }
+    if columns == 0 || rows == 0 {
+        return Err(parse_error(2, "the map has no cells"));
+    }
+
+    let mut walls = Vec::with_capacity(columns * rows);
+    for row in 0..rows {
+        let line_number = row + 5;
+        let line = lines
+            .next()
+            .ok_or_else(|| parse_error(line_number, format!("expected {rows} rows, found {row}")))?;
+        if line.chars().count() != columns {
+            return Err(parse_error(line_number, format!("expected {columns} tiles, found {}", line.chars().count())));
+        }
+        walls.extend(line.chars().map(|tile| !is_passable(tile)));
+    }
+    // the board needs somewhere to put its start and end
+    if walls.iter().filter(|&&is_wall| !is_wall).count() < 2 {
+        return Err(parse_error(5, "the map needs at least two open cells"));
+    }
+
+    Ok(MovingAiMap {
+        dimensions: GridDimensions { columns, rows },
+        walls,
+    })
+}
+
+pub fn parse_scenarios(text: &str) -> Result<Vec<Scenario>, ParseError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with("version"))
+        .map(|(index, line)| {
+            let line_number = index + 1;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // the map name sits between the bucket and the numbers and may itself contain spaces
+            if fields.len() < 9 {
+                return Err(parse_error(line_number, format!("expected 9 fields, found {}", fields.len())));
+            }
+            let numbers = &fields[fields.len() - 7..];
+            let parse_number = |field: &str| {
+                field
+                    .parse::<usize>()
+                    .map_err(|_| parse_error(line_number, format!("\"{field}\" is not a valid coordinate")))
+            };
+
+            Ok(Scenario {
+                bucket: parse_number(fields[0])?,
+                map_width: parse_number(numbers[0])?,
+                map_height: parse_number(numbers[1])?,
+                start: (parse_number(numbers[2])?, parse_number(numbers[3])?),
+                goal: (parse_number(numbers[4])?, parse_number(numbers[5])?),
+                optimal_length: numbers[6]
+                    .parse()
+                    .map_err(|_| parse_error(line_number, format!("\"{}\" is not a valid length", numbers[6])))?,
+            })
+        })
+        .collect()
+}
+
+fn get_octile_distance(from: (usize, usize), to: (usize, usize)) -> f64 {
+    let (dx, dy) = (from.0.abs_diff(to.0), from.1.abs_diff(to.1));
+
+    (dx.max(dy) - dx.min(dy)) as f64 + dx.min(dy) as f64 * SQRT_2
+}
+
+/// A cell waiting in the octile search, popped lowest priority first.
+#[derive(PartialEq)]
+struct QueuedCell {
+    priority: f64,
+    node_id: usize,
+}
+
+impl Eq for QueuedCell {}
+
+impl Ord for QueuedCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.total_cmp(&self.priority).then_with(|| other.node_id.cmp(&self.node_id))
+    }
+}
+
+impl PartialOrd for QueuedCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A reference search with the benchmark's rules rather than the board's solver, which only moves
+/// four ways: eight moves, diagonals costing √2 and never cutting the corner of a wall. Each step is
+/// scaled by the weight of the cell it enters, which is one everywhere on a loaded map. With
+/// `use_heuristic` it runs as A* on the octile distance, otherwise as Dijkstra; both are optimal.
+pub fn get_octile_length(
+    nodes: &[GridNode],
+    dimensions: GridDimensions,
+    start: (usize, usize),
+    goal: (usize, usize),
+    use_heuristic: bool,
+) -> Option<f64> {
+    let get_node_id = |(x, y): (usize, usize)| y * dimensions.columns + x;
+    let is_open = |cell: (usize, usize)| nodes[get_node_id(cell)].node_status != NodeStatus::Off;
+    let get_priority = |cell: (usize, usize), length: f64| match use_heuristic {
+        true => length + get_octile_distance(cell, goal),
+        false => length,
+    };
+
+    let mut lengths = vec![f64::INFINITY; nodes.len()];
+    let mut is_settled = vec![false; nodes.len()];
+    let mut queue = BinaryHeap::new();
+    lengths[get_node_id(start)] = 0.0;
+    queue.push(QueuedCell { priority: get_priority(start, 0.0), node_id: get_node_id(start) });
+
+    while let Some(QueuedCell { node_id, .. }) = queue.pop() {
+        if is_settled[node_id] {
+            continue;
+        }
+        is_settled[node_id] = true;
+
+        let cell = (dimensions.get_column(node_id), dimensions.get_row(node_id));
+        if cell == goal {
+            return Some(lengths[node_id]);
+        }
+
+        OCTILE_MOVES.iter().for_each(|&(dx, dy)| {
+            let (Some(x), Some(y)) = (cell.0.checked_add_signed(dx), cell.1.checked_add_signed(dy)) else {
+                return;
+            };
+            // a straight move checks its own two cells here, a diagonal also the two it squeezes between
+            if x >= dimensions.columns || y >= dimensions.rows || ![(x, y), (x, cell.1), (cell.0, y)].into_iter().all(is_open) {
+                return;
+            }
+
+            let neighbor_id = get_node_id((x, y));
+            let step = if dx != 0 && dy != 0 { SQRT_2 } else { 1.0 };
+            let length = lengths[node_id] + step * nodes[neighbor_id].weight as f64;
+            if !is_settled[neighbor_id] && length < lengths[neighbor_id] {
+                lengths[neighbor_id] = length;
+                queue.push(QueuedCell { priority: get_priority((x, y), length), node_id: neighbor_id });
+            }
+        });
+    }
+
+    None
+}
+
+/// Solves every scenario that fits the board with the reference octile search and lists those whose
+/// length differs from the optimal one. Only optimal algorithms can be held to the benchmark's
+/// lengths, so there is no report for the others.
+pub fn run_scenarios(
+    nodes: &[GridNode],
+    dimensions: GridDimensions,
+    scenarios: &[Scenario],
+    algorithm: Algorithm,
+) -> Option<ScenarioReport> {
+    if !algorithm.is_optimal() {
+        return None;
+    }
+    let mut report = ScenarioReport::default();
+
+    scenarios.iter().enumerate().for_each(|(index, scenario)| {
+        let fits_board = scenario.map_width == dimensions.columns
+            && scenario.map_height == dimensions.rows
+            && [scenario.start, scenario.goal]
+                .iter()
+                .all(|&(x, y)| x < dimensions.columns && y < dimensions.rows);
+        if !fits_board {
+            report.skipped += 1;
+            return;
+        }
+        let is_wall = |(x, y): (usize, usize)| nodes[y * dimensions.columns + x].node_status == NodeStatus::Off;
+        if is_wall(scenario.start) || is_wall(scenario.goal) {
+            report.blocked += 1;
+            return;
+        }
+
+        let found_length = get_octile_length(nodes, dimensions, scenario.start, scenario.goal, algorithm == Algorithm::AStar);
+        report.checked += 1;
+
+        let matches = found_length.is_some_and(|length| (length - scenario.optimal_length).abs() <= LENGTH_TOLERANCE);
+        if !matches {
+            report.mismatches.push(ScenarioMismatch {
+                index,
+                scenario: scenario.clone(),
+                found_length,
+            });
+        }
+    });
+
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DEFAULT_WEIGHT;
+
+    const MAP: &str = "type octile\nheight 3\nwidth 4\nmap\n....\n.@T.\n..G.\n";
+    const OPEN_MAP: &str = "type octile\nheight 3\nwidth 4\nmap\n....\n.S..\n..@.\n";
+
+    fn get_map_nodes(map: &MovingAiMap) -> Vec<GridNode> {
+        map.walls
+            .iter()
+            .map(|&is_wall| {
+                let node_status = if is_wall { NodeStatus::Off } else { NodeStatus::On };
+                GridNode::build_preview(node_status, DEFAULT_WEIGHT)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_rectangular_maps() {
+        let map = parse_map(MAP).unwrap();
+
+        assert_eq!(map.dimensions, GridDimensions { columns: 4, rows: 3 });
+        assert_eq!(
+            map.walls,
+            vec![false, false, false, false, false, true, true, false, false, false, false, false]
+        );
+    }
+
+    #[test]
+    fn reports_where_a_map_is_malformed() {
+        assert_eq!(parse_map("type octile\nheight 2\nwidth x\nmap\n").unwrap_err().line, 3);
+        assert_eq!(parse_map("type octile\nheight 2\nwidth 2\nmap\n..\n...\n").unwrap_err().line, 6);
+        assert_eq!(parse_map("type octile\nheight 2\nwidth 2\nmap\n..\n").unwrap_err().line, 6);
+        assert_eq!(
+            parse_map("type octile\nheight 2\nwidth 2\nmap\n.@\nTT\n").unwrap_err().message,
+            "the map needs at least two open cells"
+        );
+    }
+
+    #[test]
+    fn parses_scenarios_and_skips_the_version_line() {
+        let scenarios = parse_scenarios("version 1\n0\tarena.map\t4\t3\t0\t0\t3\t2\t5.00000000\n").unwrap();
+
+        assert_eq!(
+            scenarios,
+            vec![Scenario {
+                bucket: 0,
+                map_width: 4,
+                map_height: 3,
+                start: (0, 0),
+                goal: (3, 2),
+                optimal_length: 5.0,
+            }]
+        );
+        assert_eq!(parse_scenarios("version 1\n0 arena.map 4 3 0 0 3\n").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn lists_only_the_scenarios_that_disagree() {
+        let map = parse_map(MAP).unwrap();
+        let nodes = get_map_nodes(&map);
+        let scenarios = parse_scenarios(
+            "version 1\n\
+             0 a.map 4 3 0 0 3 2 5\n\
+             0 a.map 4 3 0 2 3 0 4.41421356\n\
+             0 a.map 8 8 0 0 1 1 2\n\
+             0 a.map 4 3 0 0 1 1 1.41421356\n",
+        )
+        .unwrap();
+
+        let report = run_scenarios(&nodes, map.dimensions, &scenarios, Algorithm::AStar).unwrap();
+
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.skipped, 1);
+        // (1, 1) is the wall in the middle
+        assert_eq!(report.blocked, 1);
+        assert_eq!(report.mismatches.len(), 1);
+        assert_eq!(report.mismatches[0].index, 1);
+        assert_eq!(report.mismatches[0].found_length, Some(5.0));
+        assert_eq!(run_scenarios(&nodes, map.dimensions, &scenarios, Algorithm::Dijkstra), Some(report));
+        assert_eq!(run_scenarios(&nodes, map.dimensions, &scenarios, Algorithm::GreedyBestFirst), None);
+    }
+
+    #[test]
+    fn moves_diagonally_without_cutting_corners() {
+        let map = parse_map(OPEN_MAP).unwrap();
+        let nodes = get_map_nodes(&map);
+        let get_length = |start, goal, use_heuristic| get_octile_length(&nodes, map.dimensions, start, goal, use_heuristic).unwrap();
+
+        // across the swamp, at the same cost as ground
+        assert!((get_length((0, 0), (3, 1), true) - (2.0 + SQRT_2)).abs() < LENGTH_TOLERANCE);
+        assert!((get_length((0, 0), (3, 1), false) - (2.0 + SQRT_2)).abs() < LENGTH_TOLERANCE);
+        // the wall at (2, 2) blocks the diagonal from (1, 2) to (2, 1)
+        assert_eq!(get_length((1, 2), (3, 1), true), 3.0);
+        assert_eq!(get_length((1, 1), (1, 1), true), 0.0);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::board::{GridDimensions, GridNode};
+use crate::dijkstras::{Algorithm, DijkstrasTraversal};
+use crate::rng::SeededRng;
+use crate::{NodeStatus, DEFAULT_WEIGHT};
+
+/// Seeds tried after the requested one before giving up on finding an open path.
+const MAX_PATH_ATTEMPTS: u64 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScatterOptions {
+    pub wall_density: f64,
+    pub seed: u64,
+    pub ensure_path: bool,
+}
+
+impl Default for ScatterOptions {
+    fn default() -> Self {
+        ScatterOptions {
+            wall_density: 0.3,
+            seed: 1,
+            ensure_path: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScatteredBoard {
+    pub node_statuses: Vec<NodeStatus>,
+    /// The seed that produced the board, which differs from the requested one when a regeneration was needed.
+    pub seed: u64,
+}
+
+/// Marks `wall_density` of the cells (start and end excluded) as walls, chosen by a seeded shuffle.
+pub fn scatter_walls(
+    num_nodes: usize,
+    wall_density: f64,
+    seed: u64,
+    start_node_id: usize,
+    end_node_id: usize,
+) -> Vec<NodeStatus> {
+    let mut node_statuses = vec![NodeStatus::On; num_nodes];
+    let mut candidates: Vec<usize> = (0..num_nodes)
+        .filter(|&node_id| node_id != start_node_id && node_id != end_node_id)
+        .collect();
+    let num_walls = (candidates.len() as f64 * wall_density.clamp(0.0, 1.0)).round() as usize;
+    let mut rng = SeededRng::new(seed);
+
+    // partial Fisher-Yates: the first `num_walls` candidates end up as a uniform random sample
+    for i in 0..num_walls {
+        let j = i + (rng.next_u64() % (candidates.len() - i) as u64) as usize;
+        candidates.swap(i, j);
+        node_statuses[candidates[i]] = NodeStatus::Off;
+    }
+
+    node_statuses[start_node_id] = NodeStatus::Start;
+    node_statuses[end_node_id] = NodeStatus::End;
+
+    node_statuses
+}
+
+/// Scatters walls and, if asked to, keeps trying the following seeds until start can reach end.
+pub fn scatter_walls_with_options(
+    dimensions: GridDimensions,
+    options: ScatterOptions,
+    start_node_id: usize,
+    end_node_id: usize,
+) -> Option<ScatteredBoard> {
+    (0..MAX_PATH_ATTEMPTS)
+        .map(|attempt| options.seed.wrapping_add(attempt))
+        .map(|seed| ScatteredBoard {
+            node_statuses: scatter_walls(dimensions.get_num_nodes(), options.wall_density, seed, start_node_id, end_node_id),
+            seed,
+        })
+        .find(|board| !options.ensure_path || has_path(&board.node_statuses, dimensions, start_node_id, end_node_id))
+}
+
+fn has_path(node_statuses: &[NodeStatus], dimensions: GridDimensions, start_node_id: usize, end_node_id: usize) -> bool {
+    let nodes: Vec<GridNode> = node_statuses
+        .iter()
+        .map(|node_status| GridNode::build_preview(node_status.clone(), DEFAULT_WEIGHT))
+        .collect();
+
+    // greedy best-first stops early but still finds a path whenever one exists
+    DijkstrasTraversal::run_with_algorithm(&nodes, dimensions, start_node_id, end_node_id, Algorithm::GreedyBestFirst)
+        .get_cost()
+        .is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scatter_walls_marks_requested_share_and_keeps_endpoints_open() {
+        let node_statuses = scatter_walls(100, 0.3, 9, 0, 99);
+        let num_walls = node_statuses.iter().filter(|status| **status == NodeStatus::Off).count();
+
+        assert_eq!(num_walls, 29);
+        assert_eq!(node_statuses[0], NodeStatus::Start);
+        assert_eq!(node_statuses[99], NodeStatus::End);
+    }
+
+    #[test]
+    fn scatter_walls_is_deterministic_per_seed() {
+        assert_eq!(scatter_walls(100, 0.4, 3, 0, 99), scatter_walls(100, 0.4, 3, 0, 99));
+        assert_ne!(scatter_walls(100, 0.4, 3, 0, 99), scatter_walls(100, 0.4, 4, 0, 99));
+    }
+
+    #[test]
+    fn ensure_path_regenerates_until_end_is_reachable() {
+        let options = ScatterOptions { wall_density: 0.45, seed: 0, ensure_path: true };
+
+        let unchecked = (0..20)
+            .map(|seed| scatter_walls(100, options.wall_density, seed, 0, 99))
+            .position(|node_statuses| !has_path(&node_statuses, GridDimensions::square(10), 0, 99))
+            .expect("some seed should block the path at this density");
+        let board = scatter_walls_with_options(GridDimensions::square(10), ScatterOptions { seed: unchecked as u64, ..options }, 0, 99).unwrap();
+
+        assert!(board.seed > unchecked as u64);
+        assert!(has_path(&board.node_statuses, GridDimensions::square(10), 0, 99));
+        assert_eq!(board.node_statuses, scatter_walls(100, options.wall_density, board.seed, 0, 99));
+    }
+
+    #[test]
+    fn ensure_path_gives_up_on_a_sealed_board() {
+        let options = ScatterOptions { wall_density: 1.0, seed: 7, ensure_path: true };
+
+        assert_eq!(scatter_walls_with_options(GridDimensions::square(10), options, 0, 99), None);
+        assert_eq!(scatter_walls_with_options(GridDimensions::square(10), ScatterOptions { ensure_path: false, ..options }, 0, 99).unwrap().seed, 7);
+    }
+}
@@ -0,0 +1,200 @@
+use std::borrow::Cow;
+use std::io;
+
+use gif::{Encoder, Frame, Repeat};
+
+use crate::board::GridNode;
+use crate::dijkstras::TraversalStep;
+use crate::export;
+use crate::visualizer;
+use crate::NodeStatus;
+
+pub const DEFAULT_FRAMES_PER_SECOND: u16 = 25;
+
+/// How long the finished path stays up before the animation loops, in hundredths of a second.
+const FINAL_FRAME_DELAY: u16 = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RecordingOptions {
+    pub cell_size: usize,
+    pub frames_per_second: u16,
+    pub steps_per_frame: usize,
+}
+
+impl RecordingOptions {
+    fn get_frame_delay(&self) -> u16 {
+        // most viewers treat anything under 2/100s as "as slow as possible", so never go below it
+        (100 / self.frames_per_second.max(1)).max(2)
+    }
+}
+
+/// GIF sizes are 16-bit, so an image past 65535 px a side is turned away before anything is drawn
+/// instead of coming out with its size wrapped around.
+fn get_gif_size(columns: usize, rows: usize, cell_size: usize) -> Result<(u16, u16), gif::EncodingError> {
+    let (width, height) = (export::get_image_size(columns, cell_size), export::get_image_size(rows, cell_size));
+    match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(width), Ok(height)) => Ok((width, height)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("a {width} x {height} px GIF is too large, the limit is {} px a side; try a smaller cell size", u16::MAX),
+        )
+        .into()),
+    }
+}
+
+/// Replays the step log onto `nodes` the way `visualizer::visualize` paints it, one frame per
+/// `steps_per_frame` steps, and ends on the path.
+pub fn record_gif(
+    nodes: &mut [GridNode],
+    columns: usize,
+    steps: &[TraversalStep],
+    path: &[usize],
+    options: RecordingOptions,
+) -> Result<Vec<u8>, gif::EncodingError> {
+    // every frame is rendered at full size, so the export limit holds here as well
+    export::get_checked_image_size(columns, nodes.len() / columns, options.cell_size)?;
+    let (width, height) = get_gif_size(columns, nodes.len() / columns, options.cell_size)?;
+    let (_, _, first_frame) = export::render_indexed(nodes, columns, options.cell_size);
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = Encoder::new(&mut bytes, width, height, &export::get_palette())?;
+        encoder.set_repeat(Repeat::Infinite)?;
+        encoder.write_frame(&get_full_frame(width, height, &first_frame, options.get_frame_delay()))?;
+
+        let mut previous_frame = first_frame;
+        for chunk in steps.chunks(options.steps_per_frame.max(1)) {
+            chunk
+                .iter()
+                .flat_map(|step| step.events.iter())
+                .filter_map(visualizer::get_event_paint)
+                .for_each(|(node_id, new_status)| {
+                    if let NodeStatus::Start | NodeStatus::End = nodes[node_id].node_status {
+                        return;
+                    }
+                    nodes[node_id].node_status = new_status;
+                });
+
+            let (_, _, frame) = export::render_indexed(nodes, columns, options.cell_size);
+            encoder.write_frame(&get_changed_frame(width as usize, &previous_frame, &frame, options.get_frame_delay()))?;
+            previous_frame = frame;
+        }
+
+        path.iter().for_each(|&node_id| nodes[node_id].node_status = NodeStatus::Path);
+        let (_, _, frame) = export::render_indexed(nodes, columns, options.cell_size);
+        encoder.write_frame(&get_changed_frame(width as usize, &previous_frame, &frame, FINAL_FRAME_DELAY))?;
+    }
+
+    Ok(bytes)
+}
+
+fn get_full_frame(width: u16, height: u16, pixels: &[u8], delay: u16) -> Frame<'static> {
+    Frame {
+        width,
+        height,
+        buffer: Cow::Owned(pixels.to_vec()),
+        delay,
+        ..Frame::default()
+    }
+}
+
+/// Only the bounding box of what changed is stored; earlier frames stay visible around it.
+fn get_changed_frame(width: usize, previous: &[u8], current: &[u8], delay: u16) -> Frame<'static> {
+    let changed: Vec<usize> = (0..current.len()).filter(|&i| previous[i] != current[i]).collect();
+    let (Some(left), Some(right)) = (
+        changed.iter().map(|i| i % width).min(),
+        changed.iter().map(|i| i % width).max(),
+    ) else {
+        // nothing moved this frame, but it still has to hold for its delay
+        return Frame { width: 1, height: 1, buffer: Cow::Owned(vec![current[0]]), delay, ..Frame::default() };
+    };
+    let top = changed[0] / width;
+    let bottom = changed[changed.len() - 1] / width;
+
+    let buffer = (top..=bottom)
+        .flat_map(|y| current[y * width + left..=y * width + right].iter().copied())
+        .collect();
+
+    Frame {
+        left: left as u16,
+        top: top as u16,
+        width: (right - left + 1) as u16,
+        height: (bottom - top + 1) as u16,
+        buffer: Cow::Owned(buffer),
+        delay,
+        ..Frame::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstras::{Algorithm, DijkstrasTraversal};
+    use crate::tests_common::{get_mock_grid, get_mock_nodes, MOCK_DIMENSIONS};
+
+    fn decode_frame_delays(bytes: &[u8]) -> (u16, u16, Vec<u16>) {
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(bytes).unwrap();
+        let (width, height) = (decoder.width(), decoder.height());
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+
+        (width, height, delays)
+    }
+
+    #[test]
+    fn records_one_frame_per_chunk_plus_start_and_path() {
+        let mut nodes = get_mock_nodes(get_mock_grid());
+        let traversal = DijkstrasTraversal::run_with_algorithm(&nodes, MOCK_DIMENSIONS, 0, 99, Algorithm::Dijkstra);
+        let steps = traversal.get_steps();
+        let options = RecordingOptions { cell_size: 3, frames_per_second: 10, steps_per_frame: 4 };
+
+        let bytes = record_gif(&mut nodes, 10, &steps, &traversal.get_path(), options).unwrap();
+        let (width, height, delays) = decode_frame_delays(&bytes);
+
+        assert_eq!((width, height), (41, 41));
+        assert_eq!(delays.len(), steps.len().div_ceil(4) + 2);
+        assert_eq!(delays[0], 10);
+        assert_eq!(*delays.last().unwrap(), FINAL_FRAME_DELAY);
+        assert!(traversal.get_path().iter().all(|&node_id| nodes[node_id].node_status == NodeStatus::Path));
+    }
+
+    #[test]
+    fn refuses_a_gif_too_large_for_its_16_bit_size() {
+        let mut nodes = get_mock_nodes(get_mock_grid());
+        let options = RecordingOptions { cell_size: 7000, frames_per_second: 10, steps_per_frame: 4 };
+
+        let error = record_gif(&mut nodes, 10, &[], &[], options).unwrap_err();
+
+        assert!(error.to_string().contains("too large"));
+        assert!(get_gif_size(10, 10, 6552).is_ok());
+        assert!(get_gif_size(10, 10, 6553).is_err());
+    }
+
+    #[test]
+    fn refuses_a_gif_with_too_many_pixels_to_render() {
+        let mut nodes = get_mock_nodes(get_mock_grid());
+        let options = RecordingOptions { cell_size: 1200, frames_per_second: 10, steps_per_frame: 4 };
+
+        let error = record_gif(&mut nodes, 10, &[], &[], options).unwrap_err();
+
+        assert!(error.to_string().contains("12011 x 12011 px image is too large"), "{}", error);
+    }
+
+    #[test]
+    fn changed_frame_covers_only_the_changed_box() {
+        let previous = vec![0; 16];
+        let mut current = previous.clone();
+        current[5] = 3;
+        current[10] = 4;
+
+        let frame = get_changed_frame(4, &previous, &current, 5);
+        assert_eq!((frame.left, frame.top, frame.width, frame.height), (1, 1, 2, 2));
+        assert_eq!(frame.buffer.to_vec(), vec![3, 0, 0, 4]);
+
+        let unchanged = get_changed_frame(4, &previous, &previous, 5);
+        assert_eq!((unchanged.width, unchanged.height, unchanged.delay), (1, 1, 5));
+    }
+}
@@ -0,0 +1,37 @@
+/// Small seeded generator (SplitMix64) so a seed produces the same board in the browser and on the CLI.
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        SeededRng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_produces_same_sequence() {
+        let mut first = SeededRng::new(42);
+        let mut second = SeededRng::new(42);
+        let mut other = SeededRng::new(43);
+
+        let first_values: Vec<u64> = (0..5).map(|_| first.next_u64()).collect();
+        let second_values: Vec<u64> = (0..5).map(|_| second.next_u64()).collect();
+        let other_values: Vec<u64> = (0..5).map(|_| other.next_u64()).collect();
+
+        assert_eq!(first_values, second_values);
+        assert_ne!(first_values, other_values);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::board::GridDimensions;
+use crate::rng::SeededRng;
+use crate::{DEFAULT_WEIGHT, MAX_WEIGHT};
+
+const PERMUTATION_SIZE: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TerrainOptions {
+    /// Roughly how many cells one hill spans.
+    pub scale: f64,
+    pub octaves: u32,
+    pub seed: u64,
+    /// Height in `0.0..=1.0` above which a cell becomes a wall.
+    pub wall_threshold: Option<f64>,
+}
+
+impl Default for TerrainOptions {
+    fn default() -> Self {
+        TerrainOptions {
+            scale: 12.0,
+            octaves: 3,
+            seed: 1,
+            wall_threshold: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Terrain {
+    pub weights: Vec<usize>,
+    pub walls: Vec<bool>,
+}
+
+/// Classic 2D Perlin noise over a seeded permutation table.
+struct GradientNoise {
+    permutation: Vec<usize>,
+}
+
+impl GradientNoise {
+    fn new(seed: u64) -> Self {
+        let mut rng = SeededRng::new(seed);
+        let mut permutation: Vec<usize> = (0..PERMUTATION_SIZE).collect();
+
+        for i in (1..PERMUTATION_SIZE).rev() {
+            let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+            permutation.swap(i, j);
+        }
+
+        GradientNoise { permutation }
+    }
+
+    fn hash(&self, x: i64, y: i64) -> usize {
+        let x = x.rem_euclid(PERMUTATION_SIZE as i64) as usize;
+        let y = y.rem_euclid(PERMUTATION_SIZE as i64) as usize;
+
+        self.permutation[(self.permutation[x] + y) % PERMUTATION_SIZE]
+    }
+
+    fn gradient_dot(&self, corner_x: i64, corner_y: i64, dx: f64, dy: f64) -> f64 {
+        // eight unit-ish directions are enough to hide the grid the noise is built on
+        match self.hash(corner_x, corner_y) % 8 {
+            0 => dx + dy,
+            1 => dx - dy,
+            2 => -dx + dy,
+            3 => -dx - dy,
+            4 => dx,
+            5 => -dx,
+            6 => dy,
+            _ => -dy,
+        }
+    }
+
+    fn sample(&self, x: f64, y: f64) -> f64 {
+        let cell_x = x.floor() as i64;
+        let cell_y = y.floor() as i64;
+        let dx = x - cell_x as f64;
+        let dy = y - cell_y as f64;
+
+        let top = lerp(
+            self.gradient_dot(cell_x, cell_y, dx, dy),
+            self.gradient_dot(cell_x + 1, cell_y, dx - 1.0, dy),
+            fade(dx),
+        );
+        let bottom = lerp(
+            self.gradient_dot(cell_x, cell_y + 1, dx, dy - 1.0),
+            self.gradient_dot(cell_x + 1, cell_y + 1, dx - 1.0, dy - 1.0),
+            fade(dx),
+        );
+
+        lerp(top, bottom, fade(dy))
+    }
+
+    /// Sums `octaves` layers, each at double the frequency and half the amplitude of the last.
+    fn sample_octaves(&self, x: f64, y: f64, octaves: u32) -> f64 {
+        (0..octaves.max(1))
+            .map(|octave| {
+                let frequency = (1 << octave) as f64;
+                self.sample(x * frequency, y * frequency) / frequency
+            })
+            .sum()
+    }
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+/// Heights are stretched to fill `0.0..=1.0` so every seed uses the full weight range.
+pub fn get_heights(dimensions: GridDimensions, options: TerrainOptions) -> Vec<f64> {
+    let noise = GradientNoise::new(options.seed);
+    let scale = options.scale.max(1.0);
+
+    let raw_heights: Vec<f64> = (0..dimensions.get_num_nodes())
+        .map(|node_id| {
+            let x = dimensions.get_column(node_id) as f64 / scale;
+            let y = dimensions.get_row(node_id) as f64 / scale;
+            noise.sample_octaves(x, y, options.octaves)
+        })
+        .collect();
+
+    let min = raw_heights.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = raw_heights.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    raw_heights
+        .iter()
+        .map(|height| match max - min {
+            range if range > f64::EPSILON => (height - min) / range,
+            _ => 0.0,
+        })
+        .collect()
+}
+
+pub fn generate_terrain(
+    dimensions: GridDimensions,
+    options: TerrainOptions,
+    start_node_id: usize,
+    end_node_id: usize,
+) -> Terrain {
+    let heights = get_heights(dimensions, options);
+
+    let weights = heights
+        .iter()
+        .map(|height| DEFAULT_WEIGHT + (height * (MAX_WEIGHT - DEFAULT_WEIGHT) as f64).round() as usize)
+        .collect();
+    let walls = heights
+        .iter()
+        .enumerate()
+        .map(|(node_id, &height)| match options.wall_threshold {
+            Some(threshold) => height > threshold && node_id != start_node_id && node_id != end_node_id,
+            None => false,
+        })
+        .collect();
+
+    Terrain { weights, walls }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_mean_neighbor_difference(weights: &[usize], side_length: usize) -> f64 {
+        let differences: Vec<f64> = (0..weights.len())
+            .filter(|node_id| (node_id + 1) % side_length != 0)
+            .map(|node_id| weights[node_id].abs_diff(weights[node_id + 1]) as f64)
+            .collect();
+
+        differences.iter().sum::<f64>() / differences.len() as f64
+    }
+
+    #[test]
+    fn terrain_is_deterministic_per_seed_and_within_weight_range() {
+        let options = TerrainOptions::default();
+        let terrain = generate_terrain(GridDimensions::square(30), options, 0, 899);
+
+        assert_eq!(terrain, generate_terrain(GridDimensions::square(30), options, 0, 899));
+        assert_ne!(terrain, generate_terrain(GridDimensions::square(30), TerrainOptions { seed: 2, ..options }, 0, 899));
+        assert!(terrain.weights.iter().all(|&weight| (DEFAULT_WEIGHT..=MAX_WEIGHT).contains(&weight)));
+        assert!(terrain.weights.contains(&DEFAULT_WEIGHT));
+        assert!(terrain.weights.contains(&MAX_WEIGHT));
+        assert!(terrain.walls.iter().all(|is_wall| !is_wall));
+    }
+
+    #[test]
+    fn larger_scale_gives_smoother_terrain() {
+        let smooth = generate_terrain(GridDimensions::square(40), TerrainOptions { scale: 20.0, octaves: 1, ..TerrainOptions::default() }, 0, 1599);
+        let rough = generate_terrain(GridDimensions::square(40), TerrainOptions { scale: 2.0, octaves: 1, ..TerrainOptions::default() }, 0, 1599);
+
+        assert!(get_mean_neighbor_difference(&smooth.weights, 40) < 1.0);
+        assert!(
+            get_mean_neighbor_difference(&smooth.weights, 40) < get_mean_neighbor_difference(&rough.weights, 40)
+        );
+    }
+
+    #[test]
+    fn walls_rise_above_threshold_but_never_on_endpoints() {
+        let options = TerrainOptions { wall_threshold: Some(0.6), ..TerrainOptions::default() };
+        let heights = get_heights(GridDimensions::square(30), options);
+        let terrain = generate_terrain(GridDimensions::square(30), options, 0, 899);
+
+        assert!(terrain.walls.iter().any(|&is_wall| is_wall));
+        assert!(!terrain.walls[0] && !terrain.walls[899]);
+        assert!(terrain
+            .walls
+            .iter()
+            .zip(heights.iter())
+            .enumerate()
+            .filter(|(node_id, _)| *node_id != 0 && *node_id != 899)
+            .all(|(_, (&is_wall, &height))| is_wall == (height > 0.6)));
+    }
+}
@@ -1,6 +1,9 @@
 use yew::{html, NodeRef};
 
-use crate::{board::GridNode, NodeStatus};
+use crate::board::{GridDimensions, GridNode};
+use crate::{NodeStatus, DEFAULT_WEIGHT};
+
+pub const MOCK_DIMENSIONS: GridDimensions = GridDimensions::square(10);
 
 pub fn get_mock_grid() -> Vec<usize> {
     vec![
@@ -15,11 +18,11 @@ pub fn get_mock_nodes(mock_grid: Vec<usize>) -> Vec<GridNode> {
     mock_grid
         .iter()
         .map(|node| match node {
-            0 => GridNode {node_status: NodeStatus::On, node: html! {<div></div>}, node_ref: NodeRef::default()},
-            1 => GridNode {node_status: NodeStatus::Off, node: html! {<div></div>}, node_ref: NodeRef::default()},
-            2 => GridNode {node_status: NodeStatus::Start, node: html! {<div></div>}, node_ref: NodeRef::default()},
-            3 => GridNode {node_status: NodeStatus::End, node: html! {<div></div>}, node_ref: NodeRef::default()},
-            _ => GridNode {node_status: NodeStatus::On, node: html! {<div></div>}, node_ref: NodeRef::default()},
+            0 => GridNode {node_status: NodeStatus::On, node: html! {<div></div>}, node_ref: NodeRef::default(), weight: DEFAULT_WEIGHT, direction: None, portal: None},
+            1 => GridNode {node_status: NodeStatus::Off, node: html! {<div></div>}, node_ref: NodeRef::default(), weight: DEFAULT_WEIGHT, direction: None, portal: None},
+            2 => GridNode {node_status: NodeStatus::Start, node: html! {<div></div>}, node_ref: NodeRef::default(), weight: DEFAULT_WEIGHT, direction: None, portal: None},
+            3 => GridNode {node_status: NodeStatus::End, node: html! {<div></div>}, node_ref: NodeRef::default(), weight: DEFAULT_WEIGHT, direction: None, portal: None},
+            _ => GridNode {node_status: NodeStatus::On, node: html! {<div></div>}, node_ref: NodeRef::default(), weight: DEFAULT_WEIGHT, direction: None, portal: None},
         })
         .collect()
 }
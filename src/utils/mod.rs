@@ -1,12 +1,13 @@
 use std::cell::RefMut;
 
-use web_sys::HtmlElement;
+use wasm_bindgen::JsCast;
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, HtmlElement, Url};
 use yew::MouseEvent;
 
 use crate::NodeStatus;
-use crate::{DEFAULT_COLOR, END_COLOR, OFF_COLOR, PATH_COLOR, START_COLOR, VISITED_COLOR};
+use crate::{AGENT_COLOR, DEFAULT_COLOR, DEFAULT_WEIGHT, DISCOVERED_COLOR, END_COLOR, FRONTIER_COLOR, OFF_COLOR, PATH_COLOR, START_COLOR, TRACE_COLOR, UNKNOWN_COLOR, VISITED_COLOR, WEIGHT_COLORS};
 use crate::board::{CurrentStartNode, CurrentEndNode};
-use crate::board::GridNode;
+use crate::board::{self, GridNode};
 
 pub enum Button {
     Left,
@@ -21,6 +22,7 @@ pub enum ModifierKey {
 }
 
 pub enum MouseAction {
+    Press,
     Click,
     Move,
 }
@@ -47,40 +49,110 @@ pub fn get_move_button(event: &MouseEvent) -> Button {
     }
 }
 
+pub fn get_square_color(node_status: &NodeStatus) -> &'static str {
+    match node_status {
+        NodeStatus::On => DEFAULT_COLOR,
+        NodeStatus::Off => OFF_COLOR,
+        NodeStatus::Start => START_COLOR,
+        NodeStatus::End => END_COLOR,
+        NodeStatus::Path => PATH_COLOR,
+        NodeStatus::Visited => VISITED_COLOR,
+        NodeStatus::Frontier => FRONTIER_COLOR,
+        NodeStatus::Unknown => UNKNOWN_COLOR,
+        NodeStatus::Discovered => DISCOVERED_COLOR,
+        NodeStatus::Agent => AGENT_COLOR,
+    }
+}
+
 pub fn set_square_color(node: &HtmlElement, node_status: NodeStatus) {
+    node.set_class_name(get_square_color(&node_status));
+}
+
+/// Like `get_square_color`, but open cells are shaded by how costly they are to enter.
+pub fn get_node_color(node_status: &NodeStatus, weight: usize) -> &'static str {
     match node_status {
-        NodeStatus::On => node.set_class_name(DEFAULT_COLOR),
-        NodeStatus::Off => node.set_class_name(OFF_COLOR),
-        NodeStatus::Start => node.set_class_name(START_COLOR),
-        NodeStatus::End => node.set_class_name(END_COLOR),
-        NodeStatus::Path => node.set_class_name(PATH_COLOR),
-        NodeStatus::Visited => node.set_class_name(VISITED_COLOR),
+        NodeStatus::On if weight > DEFAULT_WEIGHT => {
+            WEIGHT_COLORS[((weight - DEFAULT_WEIGHT - 1) / 2).min(WEIGHT_COLORS.len() - 1)]
+        }
+        _ => get_square_color(node_status),
     }
 }
 
-pub fn set_node_status(node_status: &mut NodeStatus, new_status: NodeStatus) {
-    *node_status = new_status;
+pub fn set_node_color(node: &HtmlElement, node_status: NodeStatus, weight: usize) {
+    node.set_class_name(get_node_color(&node_status, weight));
 }
 
-pub fn drop_first_and_last<T>(vec: &mut Vec<T>) {
-    vec.remove(0);
-    vec.pop();
+#[cfg(target_arch = "wasm32")]
+pub fn now_ms() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.now())
+        .unwrap_or(0.0)
 }
 
-pub fn set_node_on(node_ref: HtmlElement, node_status: &mut NodeStatus) {
-    set_square_color(&node_ref, NodeStatus::On);
-    set_node_status(node_status, NodeStatus::On);
+// the benchmark CLI runs natively, where the browser clock is unavailable
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now_ms() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    EPOCH.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
+
+pub fn clear_square_overlay(node: &HtmlElement) {
+    let _ = node.remove_attribute("style");
+    node.set_inner_text("");
+}
+
+pub fn add_trace_highlight(node: &HtmlElement) {
+    let _ = node.class_list().add_3("ring-2", "ring-inset", TRACE_COLOR);
 }
 
-pub fn set_node_off(node_ref: HtmlElement, node_status: &mut NodeStatus) {
-    set_square_color(&node_ref, NodeStatus::Off);
-    set_node_status(node_status, NodeStatus::Off);
+pub fn remove_trace_highlight(node: &HtmlElement) {
+    let _ = node.class_list().remove_3("ring-2", "ring-inset", TRACE_COLOR);
+}
+
+/// Every change to a cell passes through here, so it is also where the canvases learn they need a frame.
+pub fn set_node_status(node_status: &mut NodeStatus, new_status: NodeStatus) {
+    *node_status = new_status;
+    board::request_frame();
+}
+
+/// Updates the cell's status and, when the DOM grid is mounted, its color; the canvas grid
+/// only reads the status.
+pub fn paint_node(grid_node: &mut GridNode, new_status: NodeStatus) {
+    if let Some(node_ref) = grid_node.node_ref.cast::<HtmlElement>() {
+        set_node_color(&node_ref, new_status.clone(), grid_node.weight);
+    }
+    set_node_status(&mut grid_node.node_status, new_status);
+}
+
+pub fn set_node_on(grid_node: &mut GridNode) {
+    paint_node(grid_node, NodeStatus::On);
 }
 
 pub fn is_node_toggleable(node_status: &NodeStatus) -> bool {
     *node_status == NodeStatus::On || *node_status == NodeStatus::Off
 }
 
+/// Whether a search painted the cell, which is open underneath.
+pub fn is_node_searched(node_status: &NodeStatus) -> bool {
+    matches!(node_status, NodeStatus::Visited | NodeStatus::Frontier | NodeStatus::Path)
+}
+
+/// Takes what a search painted off the cell, leaving it open.
+pub fn clear_search_marks(grid_node: &mut GridNode) {
+    if !is_node_searched(&grid_node.node_status) {
+        return;
+    }
+
+    if let Some(node_ref) = grid_node.node_ref.cast::<HtmlElement>() {
+        clear_square_overlay(&node_ref);
+    }
+    set_node_on(grid_node);
+}
+
 pub fn get_modifier_key(event: &MouseEvent) -> ModifierKey {
     match event.ctrl_key() {
         true => ModifierKey::Ctrl,
@@ -97,28 +169,13 @@ pub fn set_start_node(
     current_start_node_id: CurrentStartNode,
 ) {
     if let Some(id) = current_start_node_id.borrow().as_ref() {
-        if let Some(previous_start_node) = nodes.get(*id) {
-            if let Some(previous_start_node) =
-                previous_start_node.node_ref.cast::<HtmlElement>()
-            {
-                set_square_color(&previous_start_node, NodeStatus::On);
-                set_node_status(
-                    &mut nodes[*id].node_status,
-                    NodeStatus::On,
-                );
-            }
+        if let Some(previous_start_node) = nodes.get_mut(*id) {
+            set_node_on(previous_start_node);
         }
     }
 
-    if let Some(new_start_node) = nodes.get(new_start_id).unwrap().node_ref.cast::<HtmlElement>()
-    {
-        set_square_color(&new_start_node, NodeStatus::Start);
-        set_node_status(
-            &mut nodes[new_start_id].node_status,
-            NodeStatus::Start,
-        );
-        current_start_node_id.borrow_mut().replace(new_start_id);
-    }
+    paint_node(&mut nodes[new_start_id], NodeStatus::Start);
+    current_start_node_id.borrow_mut().replace(new_start_id);
 }
 
 pub fn set_end_node(
@@ -127,25 +184,38 @@ pub fn set_end_node(
     current_end_node_id: CurrentEndNode,
 ) {
     if let Some(id) = current_end_node_id.borrow().as_ref() {
-        if let Some(previous_end_node) = nodes.get(*id) {
-            if let Some(previous_end_node) = previous_end_node.node_ref.cast::<HtmlElement>()
-            {
-                set_square_color(&previous_end_node, NodeStatus::On);
-                set_node_status(
-                    &mut nodes[*id].node_status,
-                    NodeStatus::On,
-                );
-            }
+        if let Some(previous_end_node) = nodes.get_mut(*id) {
+            set_node_on(previous_end_node);
         }
     }
 
-    if let Some(new_end_node) = nodes.get(new_end_id).unwrap().node_ref.cast::<HtmlElement>()
+    paint_node(&mut nodes[new_end_id], NodeStatus::End);
+    current_end_node_id.borrow_mut().replace(new_end_id);
+}
+
+pub fn download_file(file_name: &str, mime_type: &str, contents: &[u8]) {
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return;
+    };
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(contents));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let Ok(blob) = Blob::new_with_u8_array_sequence_and_options(&parts, &options) else {
+        return;
+    };
+    let Ok(url) = Url::create_object_url_with_blob(&blob) else {
+        return;
+    };
+
+    if let Some(anchor) = document
+        .create_element("a")
+        .ok()
+        .and_then(|element| element.dyn_into::<HtmlAnchorElement>().ok())
     {
-        set_square_color(&new_end_node, NodeStatus::End);
-        set_node_status(
-            &mut nodes[new_end_id].node_status,
-            NodeStatus::End,
-        );
-        current_end_node_id.borrow_mut().replace(new_end_id);
+        anchor.set_href(&url);
+        anchor.set_download(file_name);
+        anchor.click();
     }
-}
\ No newline at end of file
+    let _ = Url::revoke_object_url(&url);
+}
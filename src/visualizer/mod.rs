@@ -3,45 +3,340 @@ use wasm_bindgen_futures::spawn_local;
 use web_sys::HtmlElement;
 
 use crate::{utils, NodeStatus};
-use crate::board::Nodes;
+use crate::board::{GridDimensions, GridNode, Nodes};
+use crate::dijkstras::{ShortestPathTree, TraversalEvent, TraversalStep};
+use crate::fog::FogStep;
+use crate::heatmap::{self, HeatmapOptions};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AnimationSpeed {
+    Fast,
+    Medium,
+    Slow,
+    LineByLine,
+}
+
+impl AnimationSpeed {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "fast" => Some(AnimationSpeed::Fast),
+            "medium" => Some(AnimationSpeed::Medium),
+            "slow" => Some(AnimationSpeed::Slow),
+            "line" => Some(AnimationSpeed::LineByLine),
+            _ => None,
+        }
+    }
+
+    pub fn get_steps_per_frame(&self) -> usize {
+        match self {
+            AnimationSpeed::Fast => 15,
+            AnimationSpeed::Medium => 3,
+            AnimationSpeed::Slow | AnimationSpeed::LineByLine => 1,
+        }
+    }
+
+    pub fn get_frame_delay(&self) -> u32 {
+        match self {
+            AnimationSpeed::Fast => 5,
+            AnimationSpeed::Medium => 40,
+            AnimationSpeed::Slow => 300,
+            AnimationSpeed::LineByLine => 600,
+        }
+    }
+
+    /// How long the walking agent takes per unit of weight of the cell it enters.
+    pub fn get_walk_delay(&self) -> u32 {
+        match self {
+            AnimationSpeed::Fast => 30,
+            AnimationSpeed::Medium => 60,
+            AnimationSpeed::Slow | AnimationSpeed::LineByLine => 150,
+        }
+    }
+}
 
 pub fn visualize(
     nodes: Nodes,
-    traversed_nodes: Vec<usize>,
+    steps: Vec<TraversalStep>,
     path: Vec<usize>,
+    speed: AnimationSpeed,
+    is_walking: bool,
+    progress_callback: impl Fn(&[TraversalEvent], Option<usize>) + 'static,
     end_of_visualization_callback: impl Fn() + 'static,
 ) {
     spawn_local(async move {
-        let mut nodes_borrow = nodes.borrow_mut();
-        
-        for chunk in traversed_nodes.chunks(15) {
-            for &node_id in chunk {
-                if let Some(node_ref) = nodes_borrow
-                    .get(node_id)
-                    .unwrap()
-                    .node_ref
-                    .cast::<HtmlElement>()
+        let steps_per_frame = speed.get_steps_per_frame();
+
+        for (chunk_index, chunk) in steps.chunks(steps_per_frame).enumerate() {
+            let next_node_id = steps.get((chunk_index + 1) * steps_per_frame).map(|step| step.node_id);
+            let events: Vec<TraversalEvent> = chunk.iter().flat_map(|step| step.events.iter().cloned()).collect();
+            let frames: Vec<&[TraversalEvent]> = match speed {
+                AnimationSpeed::LineByLine => events.chunks(1).collect(),
+                _ => vec![&events[..]],
+            };
+
+            for frame in frames {
                 {
-                    let node_status = &mut nodes_borrow[node_id].node_status;
-                    utils::set_square_color(&node_ref, NodeStatus::Visited);
-                    utils::set_node_status(node_status, NodeStatus::Visited);
+                    let mut nodes_borrow = nodes.borrow_mut();
+                    frame.iter().for_each(|event| paint_event(&mut nodes_borrow, event));
+                }
+                progress_callback(frame, next_node_id);
+                TimeoutFuture::new(speed.get_frame_delay()).await;
+            }
+        }
+
+        paint_path(&nodes, &path);
+        if is_walking {
+            walk_path(&nodes, &path, speed).await;
+        }
+        end_of_visualization_callback();
+    });
+}
+
+/// Moves an agent along a painted path one cell at a time, lingering on each cell in proportion
+/// to its weight, so heavy terrain visibly slows it down.
+async fn walk_path(nodes: &Nodes, path: &[usize], speed: AnimationSpeed) {
+    for &node_id in path {
+        let weight = {
+            let mut nodes_borrow = nodes.borrow_mut();
+            utils::paint_node(&mut nodes_borrow[node_id], NodeStatus::Agent);
+            nodes_borrow[node_id].weight
+        };
+        TimeoutFuture::new(speed.get_walk_delay() * weight as u32).await;
+        utils::paint_node(&mut nodes.borrow_mut()[node_id], NodeStatus::Path);
+    }
+}
+
+/// Paints a finished run in one go, for re-solves that should follow an edit without delay.
+pub fn paint_instantly(nodes: &Nodes, steps: &[TraversalStep], path: &[usize]) {
+    {
+        let mut nodes_borrow = nodes.borrow_mut();
+        steps
+            .iter()
+            .flat_map(|step| step.events.iter())
+            .for_each(|event| paint_event(&mut nodes_borrow, event));
+    }
+    paint_path(nodes, path);
+}
+
+/// Paints the cells a replan expanded and its path in one go.
+pub fn paint_expanded_instantly(nodes: &Nodes, expanded: &[usize], path: &[usize]) {
+    {
+        let mut nodes_borrow = nodes.borrow_mut();
+        expanded
+            .iter()
+            .for_each(|&node_id| paint_traversed_node(&mut nodes_borrow, node_id, NodeStatus::Visited));
+    }
+    paint_path(nodes, path);
+}
+
+pub fn visualize_tree(
+    nodes: Nodes,
+    dimensions: GridDimensions,
+    tree: ShortestPathTree,
+    path: Vec<usize>,
+    options: HeatmapOptions,
+    end_of_visualization_callback: impl Fn() + 'static,
+) {
+    spawn_local(async move {
+        let max_distance = tree.max_distance();
+
+        for chunk in tree.settled_ordered.chunks(15) {
+            {
+                let mut nodes_borrow = nodes.borrow_mut();
+                for &node_id in chunk {
+                    if let NodeStatus::Start | NodeStatus::End = nodes_borrow[node_id].node_status {
+                        continue;
+                    }
+
+                    // the canvas grid has no overlays and shows these as plain visited cells
+                    if let Some(node_ref) = nodes_borrow[node_id].node_ref.cast::<HtmlElement>() {
+                        let style = heatmap::get_heat_style(tree.distances[node_id], max_distance);
+                        let label = heatmap::get_heat_label(&tree, node_id, nodes_borrow[node_id].portal, dimensions, options);
+                        heatmap::set_heat_square(&node_ref, &style, &label);
+                    }
+                    utils::set_node_status(&mut nodes_borrow[node_id].node_status, NodeStatus::Visited);
                 }
             }
             TimeoutFuture::new(5).await;
         }
 
-        for &node_id in path.iter() {
-            if let Some(node_ref) = nodes_borrow
-                .get(node_id)
-                .unwrap()
-                .node_ref
-                .cast::<HtmlElement>()
+        paint_path(&nodes, &path);
+        end_of_visualization_callback();
+    });
+}
+
+/// What an exploring agent has made of each cell so far, which decides how the cell is drawn.
+struct FogView {
+    true_statuses: Vec<NodeStatus>,
+    /// How a cell looks once seen: as itself, except that walls seen after setting off stand out.
+    seen_statuses: Vec<Option<NodeStatus>>,
+    is_trail: Vec<bool>,
+    plan: Vec<usize>,
+    position: usize,
+}
+
+impl FogView {
+    fn get_status(&self, node_id: usize) -> NodeStatus {
+        match &self.true_statuses[node_id] {
+            NodeStatus::Start | NodeStatus::End => self.true_statuses[node_id].clone(),
+            _ if node_id == self.position => NodeStatus::Agent,
+            _ if self.plan.contains(&node_id) => NodeStatus::Frontier,
+            _ if self.is_trail[node_id] => NodeStatus::Path,
+            _ => self.seen_statuses[node_id].clone().unwrap_or(NodeStatus::Unknown),
+        }
+    }
+}
+
+/// Replays a walk through the fog one move per frame: unseen cells stay hidden until the sensor
+/// reaches them, the agent leaves a trail and its current plan runs ahead of it. Once it stops,
+/// the whole board shows again with the trail painted as the path.
+pub fn visualize_fog(
+    nodes: Nodes,
+    steps: Vec<FogStep>,
+    speed: AnimationSpeed,
+    progress_callback: impl Fn(usize) + 'static,
+    end_of_visualization_callback: impl Fn() + 'static,
+) {
+    let true_statuses: Vec<NodeStatus> = nodes.borrow().iter().map(|grid_node| grid_node.node_status.clone()).collect();
+    let num_nodes = true_statuses.len();
+    let mut fog_view = FogView {
+        true_statuses,
+        seen_statuses: vec![None; num_nodes],
+        is_trail: vec![false; num_nodes],
+        plan: Vec::new(),
+        position: steps.first().map_or(0, |step| step.position),
+    };
+
+    {
+        let mut nodes_borrow = nodes.borrow_mut();
+        (0..num_nodes).for_each(|node_id| utils::paint_node(&mut nodes_borrow[node_id], fog_view.get_status(node_id)));
+    }
+
+    spawn_local(async move {
+        for (step_index, step) in steps.iter().enumerate() {
+            let previous_position = fog_view.position;
+            let previous_plan = std::mem::replace(&mut fog_view.plan, step.plan.clone());
+            fog_view.is_trail[previous_position] = true;
+            fog_view.position = step.position;
+            step.revealed.iter().for_each(|&node_id| {
+                let seen_status = match fog_view.true_statuses[node_id] {
+                    NodeStatus::Off if step_index > 0 => NodeStatus::Discovered,
+                    ref node_status => node_status.clone(),
+                };
+                fog_view.seen_statuses[node_id] = Some(seen_status);
+            });
+
             {
-                let node_status = &mut nodes_borrow[node_id].node_status;
-                utils::set_square_color(&node_ref, NodeStatus::Path);
-                utils::set_node_status(node_status, NodeStatus::Path);
+                let mut nodes_borrow = nodes.borrow_mut();
+                previous_plan
+                    .iter()
+                    .chain(&step.plan)
+                    .chain(&step.revealed)
+                    .chain([&previous_position, &step.position])
+                    .for_each(|&node_id| {
+                        let new_status = fog_view.get_status(node_id);
+                        if nodes_borrow[node_id].node_status != new_status {
+                            utils::paint_node(&mut nodes_borrow[node_id], new_status);
+                        }
+                    });
+            }
+            progress_callback(step_index);
+            TimeoutFuture::new(speed.get_frame_delay()).await;
+        }
+
+        let mut nodes_borrow = nodes.borrow_mut();
+        fog_view.is_trail[fog_view.position] = true;
+        (0..num_nodes).for_each(|node_id| {
+            let new_status = match &fog_view.true_statuses[node_id] {
+                NodeStatus::On if fog_view.is_trail[node_id] => NodeStatus::Path,
+                node_status => node_status.clone(),
+            };
+            utils::paint_node(&mut nodes_borrow[node_id], new_status);
+        });
+        drop(nodes_borrow);
+
+        end_of_visualization_callback();
+    });
+}
+
+pub struct LockstepRun {
+    pub nodes: Nodes,
+    pub steps: Vec<TraversalStep>,
+    pub path: Vec<usize>,
+}
+
+pub fn visualize_lockstep(
+    runs: Vec<LockstepRun>,
+    speed: AnimationSpeed,
+    end_of_visualization_callback: impl Fn() + 'static,
+) {
+    spawn_local(async move {
+        let steps_per_frame = speed.get_steps_per_frame();
+        let longest_run = runs.iter().map(|run| run.steps.len()).max().unwrap_or(0);
+        let mut is_path_painted = vec![false; runs.len()];
+
+        for frame_start in (0..longest_run).step_by(steps_per_frame) {
+            for (run_index, run) in runs.iter().enumerate() {
+                if frame_start >= run.steps.len() {
+                    if !is_path_painted[run_index] {
+                        paint_path(&run.nodes, &run.path);
+                        is_path_painted[run_index] = true;
+                    }
+                    continue;
+                }
+
+                let frame_end = (frame_start + steps_per_frame).min(run.steps.len());
+                let mut nodes_borrow = run.nodes.borrow_mut();
+                run.steps[frame_start..frame_end]
+                    .iter()
+                    .flat_map(|step| step.events.iter())
+                    .for_each(|event| paint_event(&mut nodes_borrow, event));
             }
+            TimeoutFuture::new(speed.get_frame_delay()).await;
         }
+
+        runs.iter()
+            .zip(is_path_painted)
+            .filter(|(_, is_painted)| !is_painted)
+            .for_each(|(run, _)| paint_path(&run.nodes, &run.path));
         end_of_visualization_callback();
     });
 }
+
+/// The cell an event repaints and its new status; shared with the recorder so exports match the screen.
+pub fn get_event_paint(event: &TraversalEvent) -> Option<(usize, NodeStatus)> {
+    match *event {
+        TraversalEvent::Pop { node_id, .. } => Some((node_id, NodeStatus::Visited)),
+        TraversalEvent::Push { node_id, .. } => Some((node_id, NodeStatus::Frontier)),
+        _ => None,
+    }
+}
+
+fn paint_event(nodes: &mut [GridNode], event: &TraversalEvent) {
+    if let Some((node_id, new_status)) = get_event_paint(event) {
+        paint_traversed_node(nodes, node_id, new_status);
+    }
+}
+
+fn paint_traversed_node(nodes: &mut [GridNode], node_id: usize, new_status: NodeStatus) {
+    if let NodeStatus::Start | NodeStatus::End = nodes[node_id].node_status {
+        return;
+    }
+
+    if let Some(node_ref) = nodes[node_id].node_ref.cast::<HtmlElement>() {
+        utils::set_square_color(&node_ref, new_status.clone());
+    }
+    utils::set_node_status(&mut nodes[node_id].node_status, new_status);
+}
+
+fn paint_path(nodes: &Nodes, path: &[usize]) {
+    let mut nodes_borrow = nodes.borrow_mut();
+
+    for &node_id in path.iter() {
+        if let Some(node_ref) = nodes_borrow[node_id].node_ref.cast::<HtmlElement>() {
+            utils::clear_square_overlay(&node_ref);
+            utils::set_square_color(&node_ref, NodeStatus::Path);
+        }
+        utils::set_node_status(&mut nodes_borrow[node_id].node_status, NodeStatus::Path);
+    }
+}
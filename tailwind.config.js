@@ -3,12 +3,6 @@ module.exports = {
   content: ["./src/**/*.rs", "./index.html"],
   theme: {
     extend: {
-      gridTemplateColumns: {
-        '50': 'repeat(50, minmax(0, 1fr))',
-      },
-      gridTemplateRows: {
-        '50': 'repeat(50, minmax(0, 1fr))',
-      },
       height: {
         '800': '800px',
         '600': '600px',